@@ -0,0 +1,353 @@
+use crate::{ProcError, ProcResult};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// The largest CPU (or memory node) number accepted by [CpuSet::from_list()]
+///
+/// This is well above the largest `CONFIG_NR_CPUS` supported by the kernel, and keeps a
+/// malformed range from expanding into billions of entries.
+pub const CPUSET_MAX: u32 = (1 << 16) - 1;
+
+/// A set of CPU (or memory node) numbers
+///
+/// The kernel displays these sets in two formats, both of which can be parsed and produced by
+/// this type:
+///
+/// * The "list format" (see [CpuSet::from_list()]), which is a comma-separated list of numbers
+///   and ranges, like `0-3,8,10-11`
+/// * The "mask format" (see [CpuSet::from_mask()]), which is a comma-separated list of 32-bit
+///   hexadecimal words, most significant first, like `00000000,00000d0f`
+///
+/// See `cpuset(7)` for details.
+///
+/// The [Display](fmt::Display) and [FromStr] implementations of this type use the list format.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct CpuSet(BTreeSet<u32>);
+
+impl CpuSet {
+    /// Creates an empty set
+    pub fn new() -> CpuSet {
+        CpuSet(BTreeSet::new())
+    }
+
+    /// Parses a set in the "list format", like `0-3,8,10-11`
+    ///
+    /// The kernel's `used/group` syntax (like `0-15:1/4`, which selects the first number of each
+    /// group of 4) is also accepted.
+    ///
+    /// Numbers above [CPUSET_MAX] are rejected.
+    pub fn from_list(s: &str) -> ProcResult<CpuSet> {
+        let mut set = BTreeSet::new();
+        for item in s.trim().split(',').filter(|i| !i.is_empty()) {
+            let (range, stride) = match item.split_once(':') {
+                Some((range, stride)) => (range, Some(stride)),
+                None => (item, None),
+            };
+            let (start, end) = match range.split_once('-') {
+                Some((start, end)) => (from_str!(u32, start), from_str!(u32, end)),
+                None => {
+                    let n = from_str!(u32, range);
+                    (n, n)
+                }
+            };
+            if start > end {
+                return Err(ProcError::Other(format!("Invalid CPU range {:?}", item)));
+            }
+            if end > CPUSET_MAX {
+                return Err(ProcError::Other(format!(
+                    "CPU number {} is larger than the maximum of {}",
+                    end, CPUSET_MAX
+                )));
+            }
+            // "used/group": take the first `used` numbers from each group of `group` numbers
+            let (used, group) = match stride {
+                Some(stride) => match stride.split_once('/') {
+                    Some((used, group)) => (from_str!(u32, used), from_str!(u32, group)),
+                    None => return Err(ProcError::Other(format!("Invalid CPU range {:?}", item))),
+                },
+                None => (1, 1),
+            };
+            if group == 0 || used == 0 || used > group {
+                return Err(ProcError::Other(format!("Invalid CPU range {:?}", item)));
+            }
+            set.extend((start..=end).filter(|n| (n - start) % group < used));
+        }
+        Ok(CpuSet(set))
+    }
+
+    /// Parses a set in the "mask format", like `00000000,00000d0f`
+    pub fn from_mask(s: &str) -> ProcResult<CpuSet> {
+        let mut words = Vec::new();
+        for word in s.trim().split(',') {
+            words.push(from_str!(u32, word, 16));
+        }
+        Ok(CpuSet::from_mask_words(&words))
+    }
+
+    /// Creates a set from a list of 32-bit mask words, most significant first
+    ///
+    /// This is the format used by the [`cpus_allowed`](crate::process::Status::cpus_allowed) and
+    /// [`mems_allowed`](crate::process::Status::mems_allowed) fields of
+    /// [Status](crate::process::Status).
+    pub fn from_mask_words(words: &[u32]) -> CpuSet {
+        let mut set = BTreeSet::new();
+        for (idx, word) in words.iter().rev().enumerate() {
+            for bit in 0..32 {
+                if word & (1 << bit) != 0 {
+                    set.insert(idx as u32 * 32 + bit);
+                }
+            }
+        }
+        CpuSet(set)
+    }
+
+    /// Formats this set in the "list format", like `0-3,8,10-11`
+    ///
+    /// This is the same as the [Display](fmt::Display) implementation.
+    pub fn to_list(&self) -> String {
+        self.to_string()
+    }
+
+    /// Formats this set in the "mask format", like `d0f`
+    ///
+    /// The most significant word is not zero-padded, and no leading zero words are included.
+    pub fn to_mask(&self) -> String {
+        let words = self.to_mask_words();
+        let mut s = format!("{:x}", words.first().copied().unwrap_or(0));
+        for word in words.iter().skip(1) {
+            s.push_str(&format!(",{:08x}", word));
+        }
+        s
+    }
+
+    /// Returns this set as a list of 32-bit mask words, most significant first
+    ///
+    /// The result has no leading zero words, and is empty if the set is empty.
+    pub fn to_mask_words(&self) -> Vec<u32> {
+        let Some(max) = self.max() else {
+            return Vec::new();
+        };
+        let mut words = vec![0u32; max as usize / 32 + 1];
+        for n in &self.0 {
+            words[*n as usize / 32] |= 1 << (n % 32);
+        }
+        words.reverse();
+        words
+    }
+
+    /// Adds a CPU to the set, returning `true` if it wasn't already present
+    pub fn insert(&mut self, cpu: u32) -> bool {
+        self.0.insert(cpu)
+    }
+
+    /// Removes a CPU from the set, returning `true` if it was present
+    pub fn remove(&mut self, cpu: u32) -> bool {
+        self.0.remove(&cpu)
+    }
+
+    /// Returns `true` if the set contains the given CPU
+    pub fn contains(&self, cpu: u32) -> bool {
+        self.0.contains(&cpu)
+    }
+
+    /// Returns the number of CPUs in the set
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the lowest CPU in the set
+    pub fn min(&self) -> Option<u32> {
+        self.0.first().copied()
+    }
+
+    /// Returns the highest CPU in the set
+    pub fn max(&self) -> Option<u32> {
+        self.0.last().copied()
+    }
+
+    /// Returns an iterator over the CPUs in the set, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns an iterator over the contiguous ranges of CPUs in the set, as inclusive `(first, last)` pairs
+    pub fn ranges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let mut iter = self.0.iter().copied().peekable();
+        std::iter::from_fn(move || {
+            let start = iter.next()?;
+            let mut end = start;
+            while iter.peek() == Some(&(end + 1)) {
+                end += 1;
+                iter.next();
+            }
+            Some((start, end))
+        })
+    }
+
+    /// Returns the CPUs that are in either set
+    pub fn union(&self, other: &CpuSet) -> CpuSet {
+        CpuSet(self.0.union(&other.0).copied().collect())
+    }
+
+    /// Returns the CPUs that are in both sets
+    pub fn intersection(&self, other: &CpuSet) -> CpuSet {
+        CpuSet(self.0.intersection(&other.0).copied().collect())
+    }
+
+    /// Returns the CPUs that are in this set, but not in `other`
+    pub fn difference(&self, other: &CpuSet) -> CpuSet {
+        CpuSet(self.0.difference(&other.0).copied().collect())
+    }
+
+    /// Returns `true` if every CPU in this set is also in `other`
+    pub fn is_subset(&self, other: &CpuSet) -> bool {
+        self.0.is_subset(&other.0)
+    }
+}
+
+impl fmt::Display for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, (start, end)) in self.ranges().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            if start == end {
+                write!(f, "{}", start)?;
+            } else {
+                write!(f, "{}-{}", start, end)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CpuSet {
+    type Err = ProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CpuSet::from_list(s)
+    }
+}
+
+impl FromIterator<u32> for CpuSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        CpuSet(iter.into_iter().collect())
+    }
+}
+
+impl Extend<u32> for CpuSet {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for CpuSet {
+    type IntoIter = std::collections::btree_set::IntoIter<u32>;
+    type Item = u32;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a CpuSet {
+    type IntoIter = std::iter::Copied<std::collections::btree_set::Iter<'a, u32>>;
+    type Item = u32;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().copied()
+    }
+}
+
+impl std::ops::BitOr for &CpuSet {
+    type Output = CpuSet;
+
+    fn bitor(self, rhs: &CpuSet) -> CpuSet {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitAnd for &CpuSet {
+    type Output = CpuSet;
+
+    fn bitand(self, rhs: &CpuSet) -> CpuSet {
+        self.intersection(rhs)
+    }
+}
+
+impl std::ops::Sub for &CpuSet {
+    type Output = CpuSet;
+
+    fn sub(self, rhs: &CpuSet) -> CpuSet {
+        self.difference(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpuset_list() {
+        let set = CpuSet::from_list("0-3,8,10-11\n").unwrap();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![(0, 3), (8, 8), (10, 11)]);
+        assert_eq!(set.to_string(), "0-3,8,10-11");
+        assert_eq!("0-3,8,10-11".parse::<CpuSet>().unwrap(), set);
+
+        assert!(CpuSet::from_list("").unwrap().is_empty());
+        assert_eq!(CpuSet::from_list("").unwrap().to_string(), "");
+        assert!(CpuSet::from_list("0-7:2").is_err());
+        assert_eq!(CpuSet::from_list("0-7:1/2").unwrap().to_string(), "0,2,4,6");
+        assert_eq!(CpuSet::from_list("0-15:2/4").unwrap().to_string(), "0-1,4-5,8-9,12-13");
+        assert!(CpuSet::from_list("3-1").is_err());
+        assert!(CpuSet::from_list("a").is_err());
+        assert!(CpuSet::from_list("0-4294967295").is_err());
+        assert!(CpuSet::from_list("70000").is_err());
+        assert_eq!(CpuSet::from_list("65535").unwrap().max(), Some(CPUSET_MAX));
+    }
+
+    #[test]
+    fn test_cpuset_mask() {
+        let set = CpuSet::from_mask("00000000,00000d0f").unwrap();
+        assert_eq!(set.to_string(), "0-3,8,10-11");
+        assert_eq!(set.to_mask(), "d0f");
+        assert_eq!(set.to_mask_words(), vec![0xd0f]);
+
+        let set = CpuSet::from_mask("1,80000001").unwrap();
+        assert_eq!(set.to_string(), "0,31-32");
+        assert_eq!(set.to_mask(), "1,80000001");
+        assert_eq!(CpuSet::from_mask_words(&[1, 0x8000_0001]), set);
+
+        assert_eq!(CpuSet::new().to_mask(), "0");
+    }
+
+    #[test]
+    fn test_cpuset_ops() {
+        let a = CpuSet::from_list("0-3").unwrap();
+        let b = CpuSet::from_list("2-5").unwrap();
+        assert_eq!((&a | &b).to_string(), "0-5");
+        assert_eq!((&a & &b).to_string(), "2-3");
+        assert_eq!((&a - &b).to_string(), "0-1");
+        assert!((&a & &b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+
+        let mut c: CpuSet = vec![5, 1, 3].into_iter().collect();
+        assert!(c.insert(2));
+        assert!(!c.insert(2));
+        assert!(c.remove(5));
+        assert!(c.contains(3));
+        assert_eq!(c.len(), 3);
+        assert_eq!((c.min(), c.max()), (Some(1), Some(3)));
+    }
+}
@@ -1,7 +1,15 @@
-use crate::{expect, from_str, ProcResult};
+use crate::{counter_delta, counter_delta_u32, expect, from_str, Devices, PartitionEntry, ProcResult};
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::io::BufRead;
+use std::time::Duration;
+
+/// The size of a sector in `/proc/diskstats`
+///
+/// The kernel always counts in 512-byte units here, regardless of the logical sector size of the
+/// device.
+pub const DISKSTATS_SECTOR_SIZE: u64 = 512;
 
 /// Disk IO stat information
 ///
@@ -154,3 +162,322 @@ impl DiskStat {
         })
     }
 }
+
+impl DiskStats {
+    /// Computes `iostat`-like metrics for every device, from the change since a previous reading
+    ///
+    /// `elapsed` is the time between the two readings.  Devices are matched by their major and
+    /// minor numbers (and name); devices that are missing from `previous` are left out.
+    ///
+    /// The kernel reports the time counters as 32-bit values, so they can wrap around on a busy
+    /// system; a time counter that went backwards is assumed to have wrapped.  When an I/O or
+    /// sector counter went backwards, the device was removed and added again with the same
+    /// numbers, so all of its counters are assumed to have started over from zero.
+    pub fn delta(&self, previous: &DiskStats, elapsed: Duration) -> Vec<IoStatReport> {
+        let previous: HashMap<(i32, i32), &DiskStat> = previous.0.iter().map(|d| ((d.major, d.minor), d)).collect();
+        self.0
+            .iter()
+            .filter_map(|now| {
+                let prev = previous.get(&(now.major, now.minor)).filter(|p| p.name == now.name)?;
+                Some(IoStatReport::new(now, prev, elapsed))
+            })
+            .collect()
+    }
+
+    /// Returns a copy of these stats without the partitions whose whole disk is also listed
+    ///
+    /// The kernel counts I/O to a partition against both the partition and its disk, so the
+    /// disk entries already include their partitions; summing them would count that I/O twice.
+    /// Partitions are identified with [PartitionEntry::parent()], using the output of
+    /// `/proc/partitions` and `/proc/devices`.
+    pub fn fold_partitions(&self, partitions: &[PartitionEntry], devices: &Devices) -> DiskStats {
+        let listed: HashSet<(i32, i32)> = self.0.iter().map(|d| (d.major, d.minor)).collect();
+        let folded: HashSet<(i32, i32)> = partitions
+            .iter()
+            .filter(|p| {
+                p.parent(partitions, devices)
+                    .is_some_and(|disk| listed.contains(&(disk.major as i32, disk.minor as i32)))
+            })
+            .map(|p| (p.major as i32, p.minor as i32))
+            .collect();
+        DiskStats(
+            self.0
+                .iter()
+                .filter(|d| !folded.contains(&(d.major, d.minor)))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// I/O metrics of a block device over an interval, like the output of `iostat -x`
+///
+/// Created by [DiskStats::delta()].  Times are in milliseconds and sizes are in bytes.  Fields for
+/// discards and flushes are `None` if the kernel doesn't report them (before 4.18 and 5.5,
+/// respectively).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct IoStatReport {
+    /// The device major number
+    pub major: i32,
+    /// The device minor number
+    pub minor: i32,
+    /// Device name
+    pub name: String,
+
+    /// Reads completed per second (`r/s`)
+    pub reads_per_sec: f64,
+    /// Writes completed per second (`w/s`)
+    pub writes_per_sec: f64,
+    /// Discards completed per second (`d/s`)
+    pub discards_per_sec: Option<f64>,
+    /// Flush requests completed per second (`f/s`)
+    pub flushes_per_sec: Option<f64>,
+
+    /// Bytes read per second
+    pub read_bytes_per_sec: f64,
+    /// Bytes written per second
+    pub write_bytes_per_sec: f64,
+    /// Bytes discarded per second
+    pub discard_bytes_per_sec: Option<f64>,
+
+    /// Read requests merged per second (`rrqm/s`)
+    pub read_merges_per_sec: f64,
+    /// Write requests merged per second (`wrqm/s`)
+    pub write_merges_per_sec: f64,
+    /// Discard requests merged per second (`drqm/s`)
+    pub discard_merges_per_sec: Option<f64>,
+    /// Percentage of read requests that were merged (`%rrqm`)
+    pub read_merge_percent: f64,
+    /// Percentage of write requests that were merged (`%wrqm`)
+    pub write_merge_percent: f64,
+    /// Percentage of discard requests that were merged (`%drqm`)
+    pub discard_merge_percent: Option<f64>,
+
+    /// Average time for a read to be served, including time spent queued (`r_await`)
+    pub read_await: f64,
+    /// Average time for a write to be served, including time spent queued (`w_await`)
+    pub write_await: f64,
+    /// Average time for a discard to be served, including time spent queued (`d_await`)
+    pub discard_await: Option<f64>,
+    /// Average time for a flush to be served, including time spent queued (`f_await`)
+    pub flush_await: Option<f64>,
+    /// Average time for reads, writes and discards to be served (`await`)
+    ///
+    /// Like `iostat`, this doesn't include flushes.
+    pub total_await: f64,
+
+    /// Average size of a read (`rareq-sz`)
+    pub avg_read_size: f64,
+    /// Average size of a write (`wareq-sz`)
+    pub avg_write_size: f64,
+    /// Average size of a discard (`dareq-sz`)
+    pub avg_discard_size: Option<f64>,
+
+    /// Average number of requests queued or being served (`aqu-sz`)
+    pub queue_size: f64,
+
+    /// Percentage of the time that the device had I/O in progress (`%util`)
+    ///
+    /// This is based on [DiskStat::time_in_progress], which counts the time during which at
+    /// least one request was in flight.  For devices that serve requests in parallel (like SSDs
+    /// and RAID arrays), 100% doesn't mean that the device is saturated.
+    pub utilization: f64,
+
+    /// I/Os in progress at the time of the latest reading
+    pub in_progress: u64,
+}
+
+impl IoStatReport {
+    fn new(now: &DiskStat, prev: &DiskStat, elapsed: Duration) -> IoStatReport {
+        let secs = elapsed.as_secs_f64();
+        let ms = secs * 1000.0;
+        let per_sec = |delta: u64| if secs > 0.0 { delta as f64 / secs } else { 0.0 };
+        let ratio = |a: u64, b: u64| if b > 0 { a as f64 / b as f64 } else { 0.0 };
+        let merge_percent = |merged: u64, ios: u64| ratio(merged * 100, merged + ios);
+
+        // the I/O and sector counters are `unsigned long`, so a counter that went backwards means
+        // that the device was removed and added again with the same number
+        let reset = now.reads < prev.reads
+            || now.writes < prev.writes
+            || now.sectors_read < prev.sectors_read
+            || now.sectors_written < prev.sectors_written;
+        // the time counters are printed as 32-bit values, and wrap around unless the device was reset
+        let time_delta = |now: u64, prev: u64| if reset { now } else { counter_delta_u32(now, prev) };
+        let option_time_delta = |now: Option<u64>, prev: Option<u64>| Some(time_delta(now?, prev?));
+
+        let reads = counter_delta(now.reads, prev.reads);
+        let writes = counter_delta(now.writes, prev.writes);
+        let discards = option_delta(now.discards, prev.discards);
+        let flushes = option_delta(now.flushes, prev.flushes);
+        let read_merged = counter_delta(now.merged, prev.merged);
+        let write_merged = counter_delta(now.writes_merged, prev.writes_merged);
+        let discard_merged = option_delta(now.discards_merged, prev.discards_merged);
+        let read_bytes = counter_delta(now.sectors_read, prev.sectors_read) * DISKSTATS_SECTOR_SIZE;
+        let write_bytes = counter_delta(now.sectors_written, prev.sectors_written) * DISKSTATS_SECTOR_SIZE;
+        let discard_bytes =
+            option_delta(now.sectors_discarded, prev.sectors_discarded).map(|s| s * DISKSTATS_SECTOR_SIZE);
+        let time_reading = time_delta(now.time_reading, prev.time_reading);
+        let time_writing = time_delta(now.time_writing, prev.time_writing);
+        let time_discarding = option_time_delta(now.time_discarding, prev.time_discarding);
+        let time_flushing = option_time_delta(now.time_flushing, prev.time_flushing);
+        let time_in_progress = time_delta(now.time_in_progress, prev.time_in_progress);
+        let weighted_time = time_delta(now.weighted_time_in_progress, prev.weighted_time_in_progress);
+
+        let utilization = if ms > 0.0 {
+            (time_in_progress as f64 / ms * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        IoStatReport {
+            major: now.major,
+            minor: now.minor,
+            name: now.name.clone(),
+            reads_per_sec: per_sec(reads),
+            writes_per_sec: per_sec(writes),
+            discards_per_sec: discards.map(per_sec),
+            flushes_per_sec: flushes.map(per_sec),
+            read_bytes_per_sec: per_sec(read_bytes),
+            write_bytes_per_sec: per_sec(write_bytes),
+            discard_bytes_per_sec: discard_bytes.map(per_sec),
+            read_merges_per_sec: per_sec(read_merged),
+            write_merges_per_sec: per_sec(write_merged),
+            discard_merges_per_sec: discard_merged.map(per_sec),
+            read_merge_percent: merge_percent(read_merged, reads),
+            write_merge_percent: merge_percent(write_merged, writes),
+            discard_merge_percent: discard_merged.zip(discards).map(|(m, d)| merge_percent(m, d)),
+            read_await: ratio(time_reading, reads),
+            write_await: ratio(time_writing, writes),
+            discard_await: time_discarding.zip(discards).map(|(t, d)| ratio(t, d)),
+            flush_await: time_flushing.zip(flushes).map(|(t, f)| ratio(t, f)),
+            total_await: ratio(
+                time_reading + time_writing + time_discarding.unwrap_or(0),
+                reads + writes + discards.unwrap_or(0),
+            ),
+            avg_read_size: ratio(read_bytes, reads),
+            avg_write_size: ratio(write_bytes, writes),
+            avg_discard_size: discard_bytes.zip(discards).map(|(b, d)| ratio(b, d)),
+            queue_size: if ms > 0.0 { weighted_time as f64 / ms } else { 0.0 },
+            utilization,
+            in_progress: now.in_progress,
+        }
+    }
+}
+
+fn option_delta(now: Option<u64>, prev: Option<u64>) -> Option<u64> {
+    Some(counter_delta(now?, prev?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromBufRead;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_delta() {
+        let before = DiskStats::from_buf_read(Cursor::new(
+            " 259       0 nvme0n1 1000 100 80000 500 2000 200 160000 4000 0 3000 4500 10 0 800 20 50 100
+ 259       1 nvme0n1p1 100 0 800 50 0 0 0 0 0 10 50 0 0 0 0 0 0
+   8       0 sda 1000 0 0 0 0 0 0 0 0 4294967000 0
+",
+        ))
+        .unwrap();
+        let after = DiskStats::from_buf_read(Cursor::new(
+            " 259       0 nvme0n1 1100 150 88000 700 2100 200 180000 4400 2 3500 5600 20 0 1600 40 60 130
+ 259       1 nvme0n1p1 200 0 1600 100 0 0 0 0 0 60 100 0 0 0 0 0 0
+   8       0 sda 1300 0 0 0 0 0 0 0 0 496 0
+   8       1 sda1 4 0 0 0 0 0 0 0 0 496 0
+",
+        ))
+        .unwrap();
+
+        let reports = after.delta(&before, Duration::from_secs(2));
+        assert_eq!(reports.len(), 3);
+
+        let nvme = &reports[0];
+        assert_eq!(nvme.name, "nvme0n1");
+        assert_eq!(nvme.reads_per_sec, 50.0);
+        assert_eq!(nvme.writes_per_sec, 50.0);
+        assert_eq!(nvme.read_bytes_per_sec, 8000.0 * 512.0 / 2.0);
+        assert_eq!(nvme.write_bytes_per_sec, 20000.0 * 512.0 / 2.0);
+        assert_eq!(nvme.read_merges_per_sec, 25.0);
+        assert_eq!(nvme.read_merge_percent, 100.0 * 50.0 / 150.0);
+        assert_eq!(nvme.write_merge_percent, 0.0);
+        assert_eq!(nvme.read_await, 2.0);
+        assert_eq!(nvme.write_await, 4.0);
+        assert_eq!(nvme.discards_per_sec, Some(5.0));
+        assert_eq!(nvme.discard_bytes_per_sec, Some(800.0 * 512.0 / 2.0));
+        assert_eq!(nvme.discard_await, Some(2.0));
+        assert_eq!(nvme.flushes_per_sec, Some(5.0));
+        assert_eq!(nvme.flush_await, Some(3.0));
+        assert_eq!(nvme.total_await, (200.0 + 400.0 + 20.0) / 210.0);
+        assert_eq!(nvme.avg_read_size, 8000.0 * 512.0 / 100.0);
+        assert_eq!(nvme.queue_size, 0.55);
+        assert_eq!(nvme.utilization, 25.0);
+        assert_eq!(nvme.in_progress, 2);
+
+        // the 32-bit time counter wrapped, and sda1 is new
+        let sda = &reports[2];
+        assert_eq!(sda.name, "sda");
+        assert_eq!(sda.reads_per_sec, 150.0);
+        assert_eq!(sda.utilization, 39.6);
+        assert_eq!(sda.discards_per_sec, None);
+        assert_eq!(sda.discard_await, None);
+    }
+
+    #[test]
+    fn test_delta_reset() {
+        let before = DiskStats::from_buf_read(Cursor::new(
+            "   8      16 sdb 6000000000 0 48000000000 3000000 0 0 0 0 0 4000000 3000000
+",
+        ))
+        .unwrap();
+        // the device was removed and added again, so all of its counters started over
+        let after = DiskStats::from_buf_read(Cursor::new(
+            "   8      16 sdb 200 0 1600 100 0 0 0 0 0 500 100
+",
+        ))
+        .unwrap();
+
+        let reports = after.delta(&before, Duration::from_secs(2));
+        let sdb = &reports[0];
+        assert_eq!(sdb.reads_per_sec, 100.0);
+        assert_eq!(sdb.read_bytes_per_sec, 1600.0 * 512.0 / 2.0);
+        assert_eq!(sdb.read_await, 0.5);
+        assert_eq!(sdb.utilization, 25.0);
+        assert_eq!(sdb.queue_size, 0.05);
+    }
+
+    #[test]
+    fn test_fold_partitions() {
+        let stats = DiskStats::from_buf_read(Cursor::new(
+            " 259       0 nvme0n1 1 0 0 0 0 0 0 0 0 0 0
+ 259       1 nvme0n1p1 1 0 0 0 0 0 0 0 0 0 0
+   7       1 loop1 1 0 0 0 0 0 0 0 0 0 0
+   7      10 loop10 1 0 0 0 0 0 0 0 0 0 0
+",
+        ))
+        .unwrap();
+        let partitions = Vec::<PartitionEntry>::from_buf_read(Cursor::new(
+            "major minor  #blocks  name
+
+ 259        0 1000204632 nvme0n1
+ 259        1    1048576 nvme0n1p1
+   7        1      65536 loop1
+   7       10      65536 loop10
+",
+        ))
+        .unwrap();
+        let devices = Devices::from_buf_read(Cursor::new(
+            "Character devices:\n\nBlock devices:\n  7 loop\n259 blkext\n",
+        ))
+        .unwrap();
+
+        let folded = stats.fold_partitions(&partitions, &devices);
+        let names: Vec<&str> = folded.0.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["nvme0n1", "loop1", "loop10"]);
+    }
+}
@@ -370,12 +370,39 @@ where
     }
 }
 
+/// The increase of a cumulative counter between two readings
+///
+/// A counter that went backwards is assumed to have been reset (for example because the device
+/// was removed and added again), so the increase is the current value.
+pub(crate) fn counter_delta(now: u64, prev: u64) -> u64 {
+    if now >= prev {
+        now - prev
+    } else {
+        now
+    }
+}
+
+/// Like [counter_delta()], for counters that the kernel keeps in 32 bits
+///
+/// A counter that went backwards from a value that fits in 32 bits is assumed to have wrapped
+/// around.
+pub(crate) fn counter_delta_u32(now: u64, prev: u64) -> u64 {
+    if now < prev && prev <= u32::MAX as u64 {
+        now + (u32::MAX as u64 - prev) + 1
+    } else {
+        counter_delta(now, prev)
+    }
+}
+
 mod cgroups;
 pub use cgroups::*;
 
 mod cpuinfo;
 pub use cpuinfo::*;
 
+mod cpuset;
+pub use cpuset::*;
+
 mod crypto;
 pub use crypto::*;
 
@@ -399,6 +426,9 @@ pub use mounts::*;
 mod partitions;
 pub use partitions::*;
 
+mod schedstat;
+pub use schedstat::*;
+
 mod meminfo;
 pub use meminfo::*;
 
@@ -748,8 +778,12 @@ impl FromBufRead for KernelConfig {
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 pub struct CpuTime {
     /// Ticks spent in user mode
+    ///
+    /// This includes [guest](CpuTime::guest) time.
     pub user: u64,
     /// Ticks spent in user mode with low priority (nice)
+    ///
+    /// This includes [guest_nice](CpuTime::guest_nice) time.
     pub nice: u64,
     /// Ticks spent in system mode
     pub system: u64,
@@ -943,6 +977,233 @@ impl CpuTime {
     pub fn guest_nice_duration(&self) -> Option<Duration> {
         self.guest_nice_ms().map(Duration::from_millis)
     }
+
+    /// Total ticks in all states
+    ///
+    /// Guest time is already included in [user](CpuTime::user) and [nice](CpuTime::nice), so it
+    /// isn't counted again.
+    pub fn total_ticks(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait.unwrap_or(0)
+            + self.irq.unwrap_or(0)
+            + self.softirq.unwrap_or(0)
+            + self.steal.unwrap_or(0)
+    }
+
+    /// Ticks spent idle or waiting for I/O to complete
+    pub fn idle_ticks(&self) -> u64 {
+        self.idle + self.iowait.unwrap_or(0)
+    }
+
+    /// Ticks spent in any state other than idle or waiting for I/O
+    pub fn busy_ticks(&self) -> u64 {
+        self.total_ticks().saturating_sub(self.idle_ticks())
+    }
+
+    /// Returns the ticks spent in each state since a previous reading
+    ///
+    /// Counters that went backwards (which can happen with `iowait`, or after a CPU was taken
+    /// offline) are treated as zero.
+    pub fn delta(&self, previous: &CpuTime) -> CpuTime {
+        fn opt(now: Option<u64>, prev: Option<u64>) -> Option<u64> {
+            Some(now?.saturating_sub(prev.unwrap_or(0)))
+        }
+
+        CpuTime {
+            user: self.user.saturating_sub(previous.user),
+            nice: self.nice.saturating_sub(previous.nice),
+            system: self.system.saturating_sub(previous.system),
+            idle: self.idle.saturating_sub(previous.idle),
+            iowait: opt(self.iowait, previous.iowait),
+            irq: opt(self.irq, previous.irq),
+            softirq: opt(self.softirq, previous.softirq),
+            steal: opt(self.steal, previous.steal),
+            guest: opt(self.guest, previous.guest),
+            guest_nice: opt(self.guest_nice, previous.guest_nice),
+            tps: self.tps,
+        }
+    }
+
+    /// Returns the percentage of time spent in each state since a previous reading
+    pub fn utilization_since(&self, previous: &CpuTime) -> CpuUtilization {
+        let delta = self.delta(previous);
+        let total = delta.total_ticks();
+        let percent = |ticks: u64| {
+            if total > 0 {
+                ticks as f64 * 100.0 / total as f64
+            } else {
+                0.0
+            }
+        };
+        let guest = delta.guest.unwrap_or(0);
+        let guest_nice = delta.guest_nice.unwrap_or(0);
+
+        CpuUtilization {
+            cpu: None,
+            busy: percent(delta.busy_ticks()),
+            user: percent(delta.user.saturating_sub(guest)),
+            nice: percent(delta.nice.saturating_sub(guest_nice)),
+            system: percent(delta.system),
+            idle: percent(delta.idle),
+            iowait: percent(delta.iowait.unwrap_or(0)),
+            irq: percent(delta.irq.unwrap_or(0)),
+            softirq: percent(delta.softirq.unwrap_or(0)),
+            steal: percent(delta.steal.unwrap_or(0)),
+            guest: percent(guest),
+            guest_nice: percent(guest_nice),
+        }
+    }
+}
+
+/// The percentage of time that a CPU (or all CPUs) spent in each state over an interval
+///
+/// Created by [CpuTime::utilization_since()] or [KernelStats::utilization_since()].
+///
+/// Unlike in [CpuTime], guest time is not included in `user` and `nice`, so (apart from `busy`)
+/// the fields add up to 100.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct CpuUtilization {
+    /// The number of the CPU
+    ///
+    /// This is `None` for the total of all CPUs, and when created by [CpuTime::utilization_since()].
+    pub cpu: Option<u32>,
+    /// Time spent in any state other than idle or waiting for I/O
+    pub busy: f64,
+    /// Time spent in user mode, not including guest time
+    pub user: f64,
+    /// Time spent in user mode with low priority (nice), not including niced guest time
+    pub nice: f64,
+    /// Time spent in system mode
+    pub system: f64,
+    /// Time spent idle
+    pub idle: f64,
+    /// Time spent idle while waiting for I/O to complete
+    pub iowait: f64,
+    /// Time spent servicing interrupts
+    pub irq: f64,
+    /// Time spent servicing softirqs
+    pub softirq: f64,
+    /// Time stolen by the hypervisor for other virtual machines
+    pub steal: f64,
+    /// Time spent running a virtual CPU for a guest
+    pub guest: f64,
+    /// Time spent running a niced guest
+    pub guest_nice: f64,
+}
+
+/// Interrupt counts, from the `intr` line of `/proc/stat`
+///
+/// New fields to this struct may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct InterruptCounts {
+    /// The total number of interrupts serviced since boot
+    ///
+    /// This includes architecture-specific interrupts (like IPIs) that don't have an IRQ number,
+    /// so it's usually larger than the sum of [counts](InterruptCounts::counts).
+    pub total: u64,
+    /// The number of times each numbered IRQ was serviced, indexed by IRQ number
+    pub counts: Vec<u64>,
+}
+
+impl InterruptCounts {
+    fn from_str(s: &str) -> ProcResult<InterruptCounts> {
+        let mut s = s.split_whitespace();
+        let total = from_str!(u64, expect!(s.next()));
+        let counts = s.map(|c| Ok(from_str!(u64, c))).collect::<ProcResult<_>>()?;
+        Ok(InterruptCounts { total, counts })
+    }
+
+    /// Returns the number of times the given IRQ was serviced
+    pub fn get(&self, irq: usize) -> Option<u64> {
+        self.counts.get(irq).copied()
+    }
+}
+
+/// Softirq counts, from the `softirq` line of `/proc/stat`
+///
+/// For the counts per CPU, see `/proc/softirqs`.  The named accessors return `None` if the kernel
+/// doesn't report that type of softirq.
+///
+/// New fields to this struct may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct SoftirqCounts {
+    /// The total number of softirqs serviced since boot
+    pub total: u64,
+    /// The number of times each type of softirq was serviced, in the order of `/proc/softirqs`
+    pub counts: Vec<u64>,
+}
+
+impl SoftirqCounts {
+    fn from_str(s: &str) -> ProcResult<SoftirqCounts> {
+        let mut s = s.split_whitespace();
+        let total = from_str!(u64, expect!(s.next()));
+        let counts = s.map(|c| Ok(from_str!(u64, c))).collect::<ProcResult<_>>()?;
+        Ok(SoftirqCounts { total, counts })
+    }
+
+    /// Returns the number of times the softirq with the given number was serviced
+    pub fn get(&self, softirq: usize) -> Option<u64> {
+        self.counts.get(softirq).copied()
+    }
+
+    /// High priority tasklets (`HI_SOFTIRQ`)
+    pub fn hi(&self) -> Option<u64> {
+        self.get(0)
+    }
+
+    /// Timers (`TIMER_SOFTIRQ`)
+    pub fn timer(&self) -> Option<u64> {
+        self.get(1)
+    }
+
+    /// Network transmit (`NET_TX_SOFTIRQ`)
+    pub fn net_tx(&self) -> Option<u64> {
+        self.get(2)
+    }
+
+    /// Network receive (`NET_RX_SOFTIRQ`)
+    pub fn net_rx(&self) -> Option<u64> {
+        self.get(3)
+    }
+
+    /// Block device completions (`BLOCK_SOFTIRQ`)
+    pub fn block(&self) -> Option<u64> {
+        self.get(4)
+    }
+
+    /// Interrupt polling (`IRQ_POLL_SOFTIRQ`, called `BLOCK_IOPOLL_SOFTIRQ` before Linux 4.4)
+    pub fn irq_poll(&self) -> Option<u64> {
+        self.get(5)
+    }
+
+    /// Normal priority tasklets (`TASKLET_SOFTIRQ`)
+    pub fn tasklet(&self) -> Option<u64> {
+        self.get(6)
+    }
+
+    /// Scheduler load balancing (`SCHED_SOFTIRQ`)
+    pub fn sched(&self) -> Option<u64> {
+        self.get(7)
+    }
+
+    /// High resolution timers (`HRTIMER_SOFTIRQ`)
+    pub fn hrtimer(&self) -> Option<u64> {
+        self.get(8)
+    }
+
+    /// RCU callbacks (`RCU_SOFTIRQ`)
+    pub fn rcu(&self) -> Option<u64> {
+        self.get(9)
+    }
 }
 
 /// Kernel/system statistics, from `/proc/stat`
@@ -972,6 +1233,113 @@ pub struct KernelStats {
     ///
     /// (Since Linux 2.5.45)
     pub procs_blocked: Option<u32>,
+
+    /// The number of the CPU of each entry of `cpu_time`
+    #[cfg_attr(feature = "serde1", serde(default))]
+    cpu_numbers: Vec<u32>,
+
+    #[cfg_attr(feature = "serde1", serde(default))]
+    intr: Option<InterruptCounts>,
+
+    #[cfg_attr(feature = "serde1", serde(default))]
+    softirq: Option<SoftirqCounts>,
+}
+
+impl KernelStats {
+    /// Returns the number of each CPU, along with the time that it spent in various states
+    ///
+    /// This is the same data as [cpu_time](KernelStats::cpu_time), but offline CPUs are left out of
+    /// `/proc/stat`, so the position in `cpu_time` isn't always the CPU number.
+    pub fn cpu_times(&self) -> impl Iterator<Item = (u32, &CpuTime)> {
+        self.cpu_numbers.iter().copied().zip(self.cpu_time.iter())
+    }
+
+    /// Number of interrupts serviced since boot
+    pub fn intr(&self) -> Option<&InterruptCounts> {
+        self.intr.as_ref()
+    }
+
+    /// Number of softirqs serviced since boot
+    ///
+    /// (Since Linux 2.6.31)
+    pub fn softirq(&self) -> Option<&SoftirqCounts> {
+        self.softirq.as_ref()
+    }
+
+    /// Computes CPU utilization and event rates since a previous reading
+    ///
+    /// The length of the interval is derived from the CPU time counters (like `vmstat` and
+    /// `mpstat` do), so no separate timestamp is needed.  CPUs that are missing from either
+    /// reading (because they were taken offline) are left out of
+    /// [cpus](KernelUtilization::cpus).
+    pub fn utilization_since(&self, previous: &KernelStats) -> KernelUtilization {
+        let cpus: Vec<CpuUtilization> = self
+            .cpu_times()
+            .filter_map(|(cpu, now)| {
+                let (_, prev) = previous.cpu_times().find(|(n, _)| *n == cpu)?;
+                Some(CpuUtilization {
+                    cpu: Some(cpu),
+                    ..now.utilization_since(prev)
+                })
+            })
+            .collect();
+
+        // every online CPU accumulates one tick per tick of wall-clock time
+        let ticks = self.total.delta(&previous.total).total_ticks() as f64;
+        let ncpus = self.cpu_time.len().max(1) as f64;
+        let elapsed = if self.total.tps > 0 {
+            Duration::from_secs_f64(ticks / ncpus / self.total.tps as f64)
+        } else {
+            Duration::ZERO
+        };
+        let secs = elapsed.as_secs_f64();
+        let per_sec = |now: u64, prev: u64| {
+            if secs > 0.0 {
+                now.saturating_sub(prev) as f64 / secs
+            } else {
+                0.0
+            }
+        };
+
+        KernelUtilization {
+            elapsed,
+            total: self.total.utilization_since(&previous.total),
+            cpus,
+            ctxt_per_sec: per_sec(self.ctxt, previous.ctxt),
+            forks_per_sec: per_sec(self.processes, previous.processes),
+            interrupts_per_sec: match (&self.intr, &previous.intr) {
+                (Some(now), Some(prev)) => Some(per_sec(now.total, prev.total)),
+                _ => None,
+            },
+            softirqs_per_sec: match (&self.softirq, &previous.softirq) {
+                (Some(now), Some(prev)) => Some(per_sec(now.total, prev.total)),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// System-wide CPU utilization and event rates over an interval
+///
+/// Created by [KernelStats::utilization_since()].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct KernelUtilization {
+    /// The approximate length of the interval, derived from the CPU time counters
+    pub elapsed: Duration,
+    /// Utilization of all CPUs combined
+    pub total: CpuUtilization,
+    /// Utilization of each CPU
+    pub cpus: Vec<CpuUtilization>,
+    /// Context switches per second
+    pub ctxt_per_sec: f64,
+    /// Processes (and threads) created per second
+    pub forks_per_sec: f64,
+    /// Interrupts serviced per second
+    pub interrupts_per_sec: Option<f64>,
+    /// Softirqs serviced per second
+    pub softirqs_per_sec: Option<f64>,
 }
 
 impl FromBufReadSI for KernelStats {
@@ -980,17 +1348,21 @@ impl FromBufReadSI for KernelStats {
 
         let mut total_cpu = None;
         let mut cpus = Vec::new();
+        let mut cpu_numbers = Vec::new();
         let mut ctxt = None;
         let mut btime = None;
         let mut processes = None;
         let mut procs_running = None;
         let mut procs_blocked = None;
+        let mut intr = None;
+        let mut softirq = None;
 
         for line in lines {
             let line = line?;
             if line.starts_with("cpu ") {
                 total_cpu = Some(CpuTime::from_str(&line, system_info.ticks_per_second())?);
-            } else if line.starts_with("cpu") {
+            } else if let Some(stripped) = line.strip_prefix("cpu") {
+                cpu_numbers.push(from_str!(u32, expect!(stripped.split_whitespace().next())));
                 cpus.push(CpuTime::from_str(&line, system_info.ticks_per_second())?);
             } else if let Some(stripped) = line.strip_prefix("ctxt ") {
                 ctxt = Some(from_str!(u64, stripped));
@@ -1002,6 +1374,11 @@ impl FromBufReadSI for KernelStats {
                 procs_running = Some(from_str!(u32, stripped));
             } else if let Some(stripped) = line.strip_prefix("procs_blocked ") {
                 procs_blocked = Some(from_str!(u32, stripped));
+            } else if let Some(stripped) = line.strip_prefix("intr ") {
+                // these lines are optional, so don't fail if they can't be parsed
+                intr = InterruptCounts::from_str(stripped).ok();
+            } else if let Some(stripped) = line.strip_prefix("softirq ") {
+                softirq = SoftirqCounts::from_str(stripped).ok();
             }
         }
 
@@ -1013,6 +1390,9 @@ impl FromBufReadSI for KernelStats {
             processes: expect!(processes),
             procs_running,
             procs_blocked,
+            cpu_numbers,
+            intr,
+            softirq,
         })
     }
 }
@@ -1149,6 +1529,111 @@ mod tests {
         assert_eq!(k.patch, 266);
     }
 
+    #[test]
+    fn test_kernel_stats_utilization() {
+        let system_info = ExplicitSystemInfo {
+            boot_time_secs: 1692972606,
+            ticks_per_second: 100,
+            page_size: 4096,
+            is_little_endian: true,
+        };
+        let before = KernelStats::from_read(
+            "cpu  1000 100 500 8000 200 10 20 30 400 0
+cpu0 500 50 250 4000 100 5 10 15 200 0
+cpu2 500 50 250 4000 100 5 10 15 200 0
+intr 5000 10 0 20
+ctxt 100000
+btime 1692972606
+processes 2000
+procs_running 1
+procs_blocked 0
+softirq 3000 1 1000 2 500 100 0 300 800 0 297
+"
+            .as_bytes(),
+            &system_info,
+        )
+        .unwrap();
+        let after = KernelStats::from_read(
+            "cpu  1300 100 600 8200 300 10 20 130 500 0
+cpu0 650 50 300 4100 150 5 10 65 250 0
+cpu2 650 50 300 4100 150 5 10 65 250 0
+intr 7000 20 0 40
+ctxt 104000
+btime 1692972606
+processes 2010
+procs_running 1
+procs_blocked 0
+softirq 4000 1 1500 2 700 200 0 300 900 0 396
+"
+            .as_bytes(),
+            &system_info,
+        )
+        .unwrap();
+
+        assert_eq!(after.cpu_times().map(|(cpu, _)| cpu).collect::<Vec<_>>(), vec![0, 2]);
+        let intr = after.intr().unwrap();
+        assert_eq!(intr.total, 7000);
+        assert_eq!(intr.get(2), Some(40));
+        assert_eq!(intr.get(3), None);
+        let softirq = after.softirq().unwrap();
+        assert_eq!(softirq.timer(), Some(1500));
+        assert_eq!(softirq.rcu(), Some(396));
+
+        // 800 ticks over 2 cpus at 100 ticks per second
+        let util = after.utilization_since(&before);
+        assert_eq!(util.elapsed, Duration::from_secs(4));
+        assert_eq!(util.ctxt_per_sec, 1000.0);
+        assert_eq!(util.forks_per_sec, 2.5);
+        assert_eq!(util.interrupts_per_sec, Some(500.0));
+        assert_eq!(util.softirqs_per_sec, Some(250.0));
+
+        // 300 user ticks, of which 100 were spent running a guest
+        assert_eq!(util.total.user, 25.0);
+        assert_eq!(util.total.guest, 12.5);
+        assert_eq!(util.total.system, 12.5);
+        assert_eq!(util.total.idle, 25.0);
+        assert_eq!(util.total.iowait, 12.5);
+        assert_eq!(util.total.steal, 12.5);
+        assert_eq!(util.total.busy, 62.5);
+        assert_eq!(util.cpus.len(), 2);
+        assert_eq!(
+            util.cpus[0],
+            CpuUtilization {
+                cpu: Some(0),
+                ..util.total.clone()
+            }
+        );
+        assert_eq!(util.cpus[1].cpu, Some(2));
+    }
+
+    #[test]
+    fn test_kernel_stats_partial_softirq() {
+        let system_info = ExplicitSystemInfo {
+            boot_time_secs: 1692972606,
+            ticks_per_second: 100,
+            page_size: 4096,
+            is_little_endian: true,
+        };
+        let stats = KernelStats::from_read(
+            "cpu  1000 100 500 8000
+cpu0 1000 100 500 8000
+intr 5000 x
+ctxt 100000
+btime 1692972606
+processes 2000
+softirq 3000 1 1000 2
+"
+            .as_bytes(),
+            &system_info,
+        )
+        .unwrap();
+        // a line that can't be parsed is left out, instead of failing the whole file
+        assert!(stats.intr().is_none());
+        let softirq = stats.softirq().unwrap();
+        assert_eq!(softirq.net_tx(), Some(2));
+        assert_eq!(softirq.net_rx(), None);
+    }
+
     #[test]
     fn test_kernel_cmp() {
         let a = KernelVersion::from_str("1.2.3").unwrap();
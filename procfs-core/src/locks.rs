@@ -147,7 +147,7 @@ pub struct Lock {
 }
 
 impl Lock {
-    fn from_line(line: &str) -> ProcResult<Lock> {
+    pub(crate) fn from_line(line: &str) -> ProcResult<Lock> {
         let mut s = line.split_whitespace();
 
         let _ = expect!(s.next());
@@ -7,12 +7,13 @@
 //! This module corresponds to the `/proc/net` directory and contains various information about the
 //! networking layer.
 use crate::ProcResult;
-use crate::{build_internal_error, expect, from_iter, from_str};
+use crate::{build_internal_error, counter_delta_u32, expect, from_iter, from_str};
 use std::collections::HashMap;
 
 use bitflags::bitflags;
 use std::io::BufRead;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::time::Duration;
 use std::{path::PathBuf, str::FromStr};
 
 #[cfg(feature = "serde1")]
@@ -577,6 +578,87 @@ impl DeviceStatus {
             sent_compressed,
         })
     }
+
+    /// Computes the rates of this interface since a previous reading
+    ///
+    /// `elapsed` is the time between the two readings.  Some counters are only 32 bits wide (for
+    /// example on 32-bit kernels, with drivers that only fill the legacy `net_device_stats`), so a
+    /// counter that went backwards from a value that fits in 32 bits is assumed to have wrapped
+    /// around.  A counter that went backwards from a larger value is assumed to have been reset,
+    /// because the interface was deleted and created again (or its driver was reloaded).
+    pub fn rates_since(&self, previous: &DeviceStatus, elapsed: Duration) -> DeviceRates {
+        let secs = elapsed.as_secs_f64();
+        let rate = |now: u64, prev: u64| {
+            if secs > 0.0 {
+                counter_delta_u32(now, prev) as f64 / secs
+            } else {
+                0.0
+            }
+        };
+
+        DeviceRates {
+            name: self.name.clone(),
+            recv_bytes: rate(self.recv_bytes, previous.recv_bytes),
+            recv_packets: rate(self.recv_packets, previous.recv_packets),
+            recv_errs: rate(self.recv_errs, previous.recv_errs),
+            recv_drop: rate(self.recv_drop, previous.recv_drop),
+            recv_fifo: rate(self.recv_fifo, previous.recv_fifo),
+            recv_frame: rate(self.recv_frame, previous.recv_frame),
+            recv_compressed: rate(self.recv_compressed, previous.recv_compressed),
+            recv_multicast: rate(self.recv_multicast, previous.recv_multicast),
+            sent_bytes: rate(self.sent_bytes, previous.sent_bytes),
+            sent_packets: rate(self.sent_packets, previous.sent_packets),
+            sent_errs: rate(self.sent_errs, previous.sent_errs),
+            sent_drop: rate(self.sent_drop, previous.sent_drop),
+            sent_fifo: rate(self.sent_fifo, previous.sent_fifo),
+            sent_colls: rate(self.sent_colls, previous.sent_colls),
+            sent_carrier: rate(self.sent_carrier, previous.sent_carrier),
+            sent_compressed: rate(self.sent_compressed, previous.sent_compressed),
+        }
+    }
+}
+
+/// Per-second rates of the counters in [DeviceStatus]
+///
+/// Created by [DeviceStatus::rates_since()] or [InterfaceDeviceStatus::rates_since()].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct DeviceRates {
+    /// Name of the interface
+    pub name: String,
+    /// Bytes received per second
+    pub recv_bytes: f64,
+    /// Packets received per second
+    pub recv_packets: f64,
+    /// Bad packets received per second
+    pub recv_errs: f64,
+    /// Received packets dropped per second
+    pub recv_drop: f64,
+    /// Fifo overruns per second
+    pub recv_fifo: f64,
+    /// Frame alignment errors per second
+    pub recv_frame: f64,
+    /// Compressed packets received per second
+    pub recv_compressed: f64,
+    /// Multicast packets received per second
+    pub recv_multicast: f64,
+    /// Bytes transmitted per second
+    pub sent_bytes: f64,
+    /// Packets transmitted per second
+    pub sent_packets: f64,
+    /// Transmission errors per second
+    pub sent_errs: f64,
+    /// Packets dropped during transmission per second
+    pub sent_drop: f64,
+    /// Transmit fifo errors per second
+    pub sent_fifo: f64,
+    /// Collisions per second
+    pub sent_colls: f64,
+    /// Carrier errors per second
+    pub sent_carrier: f64,
+    /// Compressed packets transmitted per second
+    pub sent_compressed: f64,
 }
 
 /// Device status information for all network interfaces.
@@ -597,6 +679,219 @@ impl super::FromBufRead for InterfaceDeviceStatus {
     }
 }
 
+impl InterfaceDeviceStatus {
+    /// Computes the rates of every interface since a previous reading
+    ///
+    /// `elapsed` is the time between the two readings.  Interfaces that are missing from
+    /// `previous` are left out.
+    pub fn rates_since(&self, previous: &InterfaceDeviceStatus, elapsed: Duration) -> HashMap<String, DeviceRates> {
+        self.0
+            .iter()
+            .filter_map(|(name, now)| {
+                let prev = previous.0.get(name)?;
+                Some((name.clone(), now.rates_since(prev, elapsed)))
+            })
+            .collect()
+    }
+
+    /// Combines the statistics of each interface with its wireless statistics and IPv6 addresses
+    ///
+    /// The result is sorted by interface name.  Either of `wireless` or `inet6` can be empty, for
+    /// example if the kernel doesn't have wireless extensions or IPv6 support.
+    pub fn join(&self, wireless: &WirelessEntries, inet6: &Inet6AddrEntries) -> Vec<InterfaceInfo> {
+        let mut ret: Vec<InterfaceInfo> = self
+            .0
+            .values()
+            .map(|status| InterfaceInfo {
+                name: status.name.clone(),
+                status: status.clone(),
+                wireless: wireless.0.iter().find(|w| w.name == status.name).cloned(),
+                inet6_addrs: inet6.0.iter().filter(|a| a.name == status.name).cloned().collect(),
+            })
+            .collect();
+        ret.sort_by(|a, b| a.name.cmp(&b.name));
+        ret
+    }
+}
+
+/// Statistics and addresses of a network interface, from several files in `/proc/net`
+///
+/// Created by [InterfaceDeviceStatus::join()].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct InterfaceInfo {
+    /// Name of the interface
+    pub name: String,
+    /// Statistics from `/proc/net/dev`
+    pub status: DeviceStatus,
+    /// Statistics from `/proc/net/wireless`, if this is a wireless interface
+    pub wireless: Option<WirelessEntry>,
+    /// IPv6 addresses from `/proc/net/if_inet6`
+    pub inet6_addrs: Vec<Inet6AddrEntry>,
+}
+
+impl InterfaceInfo {
+    /// Computes the rates of this interface since a previous reading
+    ///
+    /// See [DeviceStatus::rates_since()]
+    pub fn rates_since(&self, previous: &InterfaceInfo, elapsed: Duration) -> DeviceRates {
+        self.status.rates_since(&previous.status, elapsed)
+    }
+}
+
+/// Wireless statistics for a network interface, from `/proc/net/wireless`
+///
+/// The quality values are driver-specific.  The signal and noise levels are usually in dBm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct WirelessEntry {
+    /// Name of the interface
+    pub name: String,
+    /// Device dependent status
+    pub status: u16,
+    /// Overall quality of the link
+    pub link_quality: i32,
+    /// Received signal strength
+    pub signal_level: i32,
+    /// Background noise level
+    pub noise_level: i32,
+    /// Packets discarded because of a wrong network ID
+    pub discarded_nwid: u64,
+    /// Packets that couldn't be decrypted
+    pub discarded_crypt: u64,
+    /// Packets that couldn't be reassembled
+    pub discarded_frag: u64,
+    /// Packets that couldn't be delivered after retrying
+    pub discarded_retry: u64,
+    /// Packets lost for other reasons
+    pub discarded_misc: u64,
+    /// Number of missed beacons
+    pub missed_beacon: u64,
+}
+
+/// Wireless statistics for all wireless network interfaces
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct WirelessEntries(pub Vec<WirelessEntry>);
+
+impl super::FromBufRead for WirelessEntries {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        // a trailing '.' on a quality value means that it was updated since it was last read
+        fn quality(s: Option<&str>) -> ProcResult<i32> {
+            Ok(from_str!(i32, expect!(s).trim_end_matches('.')))
+        }
+
+        let mut vec = Vec::new();
+        // the first two lines are headers, so skip them
+        for line in r.lines().skip(2) {
+            let line = line?;
+            let (name, rest) = expect!(line.split_once(':'));
+            let mut s = rest.split_whitespace();
+            vec.push(WirelessEntry {
+                name: name.trim().to_string(),
+                status: from_str!(u16, expect!(s.next()), 16),
+                link_quality: quality(s.next())?,
+                signal_level: quality(s.next())?,
+                noise_level: quality(s.next())?,
+                discarded_nwid: from_str!(u64, expect!(s.next())),
+                discarded_crypt: from_str!(u64, expect!(s.next())),
+                discarded_frag: from_str!(u64, expect!(s.next())),
+                discarded_retry: from_str!(u64, expect!(s.next())),
+                discarded_misc: from_str!(u64, expect!(s.next())),
+                missed_beacon: from_str!(u64, expect!(s.next())),
+            });
+        }
+        Ok(WirelessEntries(vec))
+    }
+}
+
+bitflags! {
+    /// Flags for IPv6 interface addresses
+    ///
+    /// `/proc/net/if_inet6` only shows the low 8 bits of the flags, so [MANAGETEMPADDR] and the
+    /// flags above it never appear in an [Inet6AddrEntry].
+    ///
+    /// [MANAGETEMPADDR]: Inet6AddrFlags::MANAGETEMPADDR
+    // source: include/uapi/linux/if_addr.h
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct Inet6AddrFlags: u32 {
+        /// A temporary (privacy extension) address
+        const TEMPORARY = 0x01;
+        /// Duplicate address detection is disabled
+        const NODAD = 0x02;
+        /// Optimistic duplicate address detection is in progress
+        const OPTIMISTIC = 0x04;
+        /// Duplicate address detection failed
+        const DADFAILED = 0x08;
+        /// A home address for mobile IPv6
+        const HOMEADDRESS = 0x10;
+        /// The preferred lifetime of the address has expired
+        const DEPRECATED = 0x20;
+        /// Duplicate address detection hasn't finished yet
+        const TENTATIVE = 0x40;
+        /// A permanent address (not created by autoconfiguration)
+        const PERMANENT = 0x80;
+        /// Temporary addresses are created from this address
+        const MANAGETEMPADDR = 0x100;
+        /// No prefix route is created for this address
+        const NOPREFIXROUTE = 0x200;
+        /// Joins the multicast group for this address
+        const MCAUTOJOIN = 0x400;
+        /// Uses a stable privacy (RFC 7217) interface identifier
+        const STABLE_PRIVACY = 0x800;
+    }
+}
+
+/// An IPv6 address of a network interface, from `/proc/net/if_inet6`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Inet6AddrEntry {
+    /// The IPv6 address
+    pub address: Ipv6Addr,
+    /// The index of the interface
+    pub ifindex: u32,
+    /// The length of the network prefix
+    pub prefix_len: u8,
+    /// The scope of the address
+    ///
+    /// This is `0x00` for global addresses, `0x10` for the loopback address, `0x20` for
+    /// link-local addresses and `0x40` for site-local addresses.
+    pub scope: u8,
+    /// The flags of the address
+    ///
+    /// Only the low 8 bits of the flags are available (see [Inet6AddrFlags]).
+    pub flags: Inet6AddrFlags,
+    /// Name of the interface
+    pub name: String,
+}
+
+/// The IPv6 addresses of all network interfaces
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Inet6AddrEntries(pub Vec<Inet6AddrEntry>);
+
+impl super::FromBufRead for Inet6AddrEntries {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut vec = Vec::new();
+        for line in r.lines() {
+            let line = line?;
+            let mut s = line.split_whitespace();
+            let address = from_str!(u128, expect!(s.next()), 16);
+            vec.push(Inet6AddrEntry {
+                address: Ipv6Addr::from(address),
+                ifindex: from_str!(u32, expect!(s.next()), 16),
+                prefix_len: from_str!(u8, expect!(s.next()), 16),
+                scope: from_str!(u8, expect!(s.next()), 16),
+                flags: Inet6AddrFlags::from_bits_retain(from_str!(u32, expect!(s.next()), 16)),
+                name: expect!(s.next()).to_string(),
+            });
+        }
+        Ok(Inet6AddrEntries(vec))
+    }
+}
+
 /// An entry in the ipv4 route table
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
@@ -1728,4 +2023,107 @@ UdpLite: 0 0 0 0 0 0 0 0 0
         let res = Snmp::from_read(r).unwrap();
         println!("{res:?}");
     }
+
+    #[test]
+    fn test_dev_rates() {
+        use crate::FromRead;
+        let header = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+";
+        let before = InterfaceDeviceStatus::from_read(
+            format!(
+                "{}    lo:    1000      10    0    0    0     0          0         0     1000      10    0    0    0     0       0          0
+  eth0:    1000     100    1    2    0     0          0         5  8000000000     200    0    0    0     0       0          0
+  eth1: 5000000000 5000000000 0    0    0     0          0         0  6000000000 6000000000    0    0    0     0       0          0
+  eth2: 4294967280 4294967295 4294967290 0 0 0          0         0  4294967295 4294967295    0    0    0     0       0          0
+",
+                header
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        let after = InterfaceDeviceStatus::from_read(
+            format!(
+                "{}    lo:    3000      30    0    0    0     0          0         0     3000      30    0    0    0     0       0          0
+  eth0:    2000     140    3    2    0     0          0         9  8000002000     220    0    0    0     0       0          0
+  eth1:     704       4    0    0    0     0          0         0      900       6    0    0    0     0       0          0
+  eth2:      16       1    2    0    0     0          0         0       99       1    0    0    0     0       0          0
+ wlan0:       0       0    0    0    0     0          0         0        0       0    0    0    0     0       0          0
+",
+                header
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let rates = after.rates_since(&before, Duration::from_secs(2));
+        assert_eq!(rates.len(), 4);
+        assert_eq!(rates["lo"].recv_bytes, 1000.0);
+        assert_eq!(rates["lo"].sent_packets, 10.0);
+        assert_eq!(rates["eth0"].recv_bytes, 500.0);
+        assert_eq!(rates["eth0"].recv_packets, 20.0);
+        assert_eq!(rates["eth0"].recv_errs, 1.0);
+        assert_eq!(rates["eth0"].recv_drop, 0.0);
+        assert_eq!(rates["eth0"].recv_multicast, 2.0);
+        assert_eq!(rates["eth0"].sent_bytes, 1000.0);
+        // eth1 was deleted and created again, so its counters started over
+        assert_eq!(rates["eth1"].recv_bytes, 352.0);
+        assert_eq!(rates["eth1"].recv_packets, 2.0);
+        assert_eq!(rates["eth1"].recv_errs, 0.0);
+        assert_eq!(rates["eth1"].sent_bytes, 450.0);
+        assert_eq!(rates["eth1"].sent_packets, 3.0);
+        // the 32-bit counters of eth2 wrapped
+        assert_eq!(rates["eth2"].recv_bytes, 16.0);
+        assert_eq!(rates["eth2"].recv_packets, 1.0);
+        assert_eq!(rates["eth2"].recv_errs, 4.0);
+        assert_eq!(rates["eth2"].sent_bytes, 50.0);
+        assert_eq!(rates["eth2"].sent_packets, 1.0);
+
+        let wireless = WirelessEntries::from_read(
+            "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+ wlan0: 0000   70.  -40.  -256        0      1      0      0     12        3
+"
+            .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(wireless.0.len(), 1);
+        assert_eq!(wireless.0[0].name, "wlan0");
+        assert_eq!(wireless.0[0].link_quality, 70);
+        assert_eq!(wireless.0[0].signal_level, -40);
+        assert_eq!(wireless.0[0].noise_level, -256);
+        assert_eq!(wireless.0[0].discarded_crypt, 1);
+        assert_eq!(wireless.0[0].discarded_misc, 12);
+        assert_eq!(wireless.0[0].missed_beacon, 3);
+
+        let inet6 = Inet6AddrEntries::from_read(
+            "00000000000000000000000000000001 01 80 10 80       lo
+fe80000000000000021122fffe334455 02 40 20 80     eth0
+20010db8000000000000000000000001 02 40 00 01     eth0
+"
+            .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(inet6.0.len(), 3);
+        assert_eq!(inet6.0[0].address, Ipv6Addr::LOCALHOST);
+        assert_eq!(inet6.0[0].prefix_len, 128);
+        assert_eq!(inet6.0[0].scope, 0x10);
+        assert_eq!(
+            inet6.0[1].address,
+            Ipv6Addr::from_str("fe80::211:22ff:fe33:4455").unwrap()
+        );
+        assert_eq!(inet6.0[1].ifindex, 2);
+        assert_eq!(inet6.0[1].flags, Inet6AddrFlags::PERMANENT);
+        assert_eq!(inet6.0[2].flags, Inet6AddrFlags::TEMPORARY);
+
+        let interfaces = after.join(&wireless, &inet6);
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["eth0", "eth1", "eth2", "lo", "wlan0"]);
+        assert_eq!(interfaces[0].inet6_addrs.len(), 2);
+        assert!(interfaces[0].wireless.is_none());
+        assert!(interfaces[1].inet6_addrs.is_empty());
+        assert_eq!(interfaces[3].inet6_addrs.len(), 1);
+        assert_eq!(interfaces[4].wireless.as_ref().unwrap().missed_beacon, 3);
+        assert!(interfaces[4].inet6_addrs.is_empty());
+    }
 }
@@ -1,6 +1,6 @@
 use std::io::BufRead;
 
-use super::ProcResult;
+use super::{Devices, ProcResult};
 use std::str::FromStr;
 
 #[cfg(feature = "serde1")]
@@ -49,6 +49,44 @@ impl super::FromBufRead for Vec<PartitionEntry> {
     }
 }
 
+impl PartitionEntry {
+    /// Finds the whole disk that this partition belongs to
+    ///
+    /// Returns `None` if this entry is a whole disk (or if its parent isn't listed in `partitions`).
+    ///
+    /// `/proc/partitions` doesn't record which entries are partitions, so this uses the kernel's
+    /// naming scheme: a partition of `sda` is named `sda1`, and a partition of a disk whose name
+    /// ends in a digit (like `nvme0n1` or `mmcblk0`) is named `nvme0n1p1`.  Partitions normally
+    /// share the major number of their disk, except when they use the extended `blkext` major
+    /// number from `devices`.
+    pub fn parent<'a>(&self, partitions: &'a [PartitionEntry], devices: &Devices) -> Option<&'a PartitionEntry> {
+        let blkext = devices
+            .block_devices
+            .iter()
+            .any(|d| d.name == "blkext" && d.major == self.major as i32);
+
+        partitions.iter().find(|disk| {
+            if disk.name.is_empty() || (disk.major != self.major && !blkext) {
+                return false;
+            }
+            let suffix = match self.name.strip_prefix(disk.name.as_str()) {
+                Some(suffix) => suffix,
+                None => return false,
+            };
+            // "loop10" is not a partition of "loop1"
+            let number = if disk.name.ends_with(|c: char| c.is_ascii_digit()) {
+                match suffix.strip_prefix('p') {
+                    Some(number) => number,
+                    None => return false,
+                }
+            } else {
+                suffix
+            };
+            !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit())
+        })
+    }
+}
+
 #[test]
 fn test_partitions() {
     use crate::FromBufRead;
@@ -84,3 +122,39 @@ fn test_partitions() {
     assert_eq!(partitions[11].blocks, 1853440);
     assert_eq!(partitions[11].name, "dm-2");
 }
+
+#[test]
+fn test_partition_parent() {
+    use crate::FromBufRead;
+    use std::io::Cursor;
+
+    let s = "major minor  #blocks  name
+
+ 259        0 1000204632 nvme0n1
+ 259        1    1048576 nvme0n1p1
+   8        0    3953664 sda
+   8        1    2097152 sda1
+ 179        0   15558144 mmcblk0
+ 179        1     262144 mmcblk0p1
+   7        1      65536 loop1
+   7       10      65536 loop10
+ 253        0  104841216 dm-0
+";
+    let partitions = Vec::<PartitionEntry>::from_buf_read(Cursor::new(s)).unwrap();
+    let devices = Devices::from_buf_read(Cursor::new(
+        "Character devices:\n  1 mem\n\nBlock devices:\n  7 loop\n  8 sd\n179 mmc\n253 device-mapper\n259 blkext\n",
+    ))
+    .unwrap();
+
+    let parent = |name: &str| {
+        let entry = partitions.iter().find(|p| p.name == name).unwrap();
+        entry.parent(&partitions, &devices).map(|p| p.name.as_str())
+    };
+    assert_eq!(parent("nvme0n1p1"), Some("nvme0n1"));
+    assert_eq!(parent("sda1"), Some("sda"));
+    assert_eq!(parent("mmcblk0p1"), Some("mmcblk0"));
+    assert_eq!(parent("nvme0n1"), None);
+    assert_eq!(parent("sda"), None);
+    assert_eq!(parent("loop10"), None);
+    assert_eq!(parent("dm-0"), None);
+}
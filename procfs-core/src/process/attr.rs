@@ -0,0 +1,256 @@
+use crate::{ProcError, ProcResult};
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// One of the security attribute files in the `/proc/<pid>/attr/` directory
+///
+/// See `proc_pid_attr(5)` for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub enum SecurityAttr {
+    /// The current security attributes of the process (`attr/current`)
+    Current,
+    /// The security attributes to be assigned to the process on the next `execve(2)` (`attr/exec`)
+    Exec,
+    /// The security attributes to be assigned to files created by the process (`attr/fscreate`)
+    FsCreate,
+    /// The security attributes to be assigned to keys created by the process (`attr/keycreate`)
+    KeyCreate,
+    /// The security attributes of the process before the last `execve(2)` (`attr/prev`)
+    Prev,
+    /// The security attributes to be assigned to sockets created by the process (`attr/sockcreate`)
+    SockCreate,
+}
+
+impl SecurityAttr {
+    /// The name of the file in the `/proc/<pid>/attr/` directory
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            SecurityAttr::Current => "current",
+            SecurityAttr::Exec => "exec",
+            SecurityAttr::FsCreate => "fscreate",
+            SecurityAttr::KeyCreate => "keycreate",
+            SecurityAttr::Prev => "prev",
+            SecurityAttr::SockCreate => "sockcreate",
+        }
+    }
+}
+
+/// An SELinux security context, in the form `user:role:type:level`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct SELinuxContext {
+    /// The SELinux user, for example `system_u`
+    pub user: String,
+    /// The role, for example `system_r`
+    pub role: String,
+    /// The type (or domain), for example `httpd_t`
+    pub type_: String,
+    /// The MLS/MCS sensitivity level and categories, for example `s0-s0:c0.c1023`
+    ///
+    /// This is `None` if the policy doesn't use MLS.
+    pub level: Option<String>,
+}
+
+impl FromStr for SELinuxContext {
+    type Err = ProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(4, ':');
+        Ok(SELinuxContext {
+            user: expect!(parts.next()).to_string(),
+            role: expect!(parts.next()).to_string(),
+            type_: expect!(parts.next()).to_string(),
+            level: parts.next().map(|l| l.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for SELinuxContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.user, self.role, self.type_)?;
+        if let Some(level) = &self.level {
+            write!(f, ":{}", level)?;
+        }
+        Ok(())
+    }
+}
+
+/// The mode of an AppArmor profile
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum AppArmorMode {
+    /// The profile is enforced, and violations are logged
+    Enforce,
+    /// The profile is not enforced, but violations are logged
+    Complain,
+    /// The profile is enforced, and violations kill the process
+    Kill,
+    /// The profile is not enforced
+    Unconfined,
+    /// Some other unknown mode
+    Other(String),
+}
+
+impl From<&str> for AppArmorMode {
+    fn from(s: &str) -> AppArmorMode {
+        match s {
+            "enforce" => AppArmorMode::Enforce,
+            "complain" => AppArmorMode::Complain,
+            "kill" => AppArmorMode::Kill,
+            "unconfined" => AppArmorMode::Unconfined,
+            x => AppArmorMode::Other(x.to_string()),
+        }
+    }
+}
+
+impl AppArmorMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AppArmorMode::Enforce => "enforce",
+            AppArmorMode::Complain => "complain",
+            AppArmorMode::Kill => "kill",
+            AppArmorMode::Unconfined => "unconfined",
+            AppArmorMode::Other(s) => s.as_ref(),
+        }
+    }
+}
+
+/// An AppArmor label, in the form `profile (mode)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct AppArmorContext {
+    /// The name of the profile (or stack of profiles, separated by `//&`)
+    ///
+    /// This is `unconfined` for processes that are not confined by any profile.
+    pub profile: String,
+    /// The mode of the profile
+    ///
+    /// This is `None` for unconfined processes.
+    pub mode: Option<AppArmorMode>,
+}
+
+impl AppArmorContext {
+    /// Returns true if the process is not confined by any profile
+    pub fn is_unconfined(&self) -> bool {
+        self.profile == "unconfined" && self.mode.is_none()
+    }
+}
+
+impl FromStr for AppArmorContext {
+    type Err = ProcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_suffix(')').and_then(|s| s.rsplit_once(" (")) {
+            Some((profile, mode)) => Ok(AppArmorContext {
+                profile: profile.to_string(),
+                mode: Some(AppArmorMode::from(mode)),
+            }),
+            None => Ok(AppArmorContext {
+                profile: s.to_string(),
+                mode: None,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for AppArmorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.mode {
+            Some(mode) => write!(f, "{} ({})", self.profile, mode.as_str()),
+            None => write!(f, "{}", self.profile),
+        }
+    }
+}
+
+/// A security label, as assigned by a Linux Security Module (LSM)
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum SecurityContext {
+    /// An SELinux context
+    SELinux(SELinuxContext),
+    /// An AppArmor label
+    AppArmor(AppArmorContext),
+    /// A Smack label
+    Smack(String),
+    /// A label from some other LSM, or one that couldn't be recognized
+    Unknown(String),
+}
+
+impl SecurityContext {
+    /// Guesses which LSM a label from `/proc/<pid>/attr/current` belongs to, based on its format
+    ///
+    /// SELinux contexts are recognized by their `user:role:type` structure, and AppArmor labels by
+    /// their `profile (mode)` structure.  Anything else is returned as
+    /// [`Unknown`](SecurityContext::Unknown).
+    pub fn from_current(s: &str) -> ProcResult<SecurityContext> {
+        let s = s.trim();
+        if (s.ends_with(')') && s.contains(" (")) || s == "unconfined" {
+            Ok(SecurityContext::AppArmor(s.parse()?))
+        } else if s.splitn(4, ':').count() >= 3 && !s.contains(char::is_whitespace) {
+            Ok(SecurityContext::SELinux(s.parse()?))
+        } else {
+            Ok(SecurityContext::Unknown(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for SecurityContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecurityContext::SELinux(c) => write!(f, "{}", c),
+            SecurityContext::AppArmor(c) => write!(f, "{}", c),
+            SecurityContext::Smack(s) | SecurityContext::Unknown(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_context() {
+        let ctx = SecurityContext::from_current("unconfined_u:unconfined_r:unconfined_t:s0-s0:c0.c1023\n").unwrap();
+        let SecurityContext::SELinux(se) = &ctx else { panic!() };
+        assert_eq!(se.user, "unconfined_u");
+        assert_eq!(se.role, "unconfined_r");
+        assert_eq!(se.type_, "unconfined_t");
+        assert_eq!(se.level.as_deref(), Some("s0-s0:c0.c1023"));
+        assert_eq!(ctx.to_string(), "unconfined_u:unconfined_r:unconfined_t:s0-s0:c0.c1023");
+
+        let ctx = SecurityContext::from_current("system_u:system_r:httpd_t").unwrap();
+        assert!(matches!(
+            ctx,
+            SecurityContext::SELinux(SELinuxContext { level: None, .. })
+        ));
+
+        let ctx = SecurityContext::from_current("/usr/sbin/cupsd (enforce)\n").unwrap();
+        let SecurityContext::AppArmor(aa) = &ctx else { panic!() };
+        assert_eq!(aa.profile, "/usr/sbin/cupsd");
+        assert_eq!(aa.mode, Some(AppArmorMode::Enforce));
+        assert_eq!(ctx.to_string(), "/usr/sbin/cupsd (enforce)");
+
+        let ctx = SecurityContext::from_current("firefox//&snap.firefox (complain)").unwrap();
+        let SecurityContext::AppArmor(aa) = &ctx else { panic!() };
+        assert_eq!(aa.profile, "firefox//&snap.firefox");
+        assert_eq!(aa.mode, Some(AppArmorMode::Complain));
+
+        let ctx = SecurityContext::from_current("unconfined").unwrap();
+        let SecurityContext::AppArmor(aa) = &ctx else { panic!() };
+        assert!(aa.is_unconfined());
+
+        let ctx = SecurityContext::from_current("kernel").unwrap();
+        assert_eq!(ctx, SecurityContext::Unknown("kernel".to_string()));
+    }
+}
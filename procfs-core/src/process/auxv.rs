@@ -0,0 +1,419 @@
+use crate::ProcResult;
+use bitflags::bitflags;
+use std::io::Read;
+use std::mem::size_of;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+macro_rules! auxv_keys {
+    ($($(#[$meta:meta])* $name:ident = $value:expr,)*) => {
+        /// A key in the auxiliary vector
+        ///
+        /// See `getauxval(3)` and `include/uapi/linux/auxvec.h` for details about each key.
+        ///
+        /// New variants to this enum may be added at any time (even without a major or minor semver bump).
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[non_exhaustive]
+        pub enum AuxvKey {
+            $($(#[$meta])* $name,)*
+            /// A key that isn't known to this crate
+            Unknown(u64),
+        }
+
+        impl From<u64> for AuxvKey {
+            fn from(key: u64) -> Self {
+                match key {
+                    $($value => AuxvKey::$name,)*
+                    key => AuxvKey::Unknown(key),
+                }
+            }
+        }
+
+        impl From<AuxvKey> for u64 {
+            fn from(key: AuxvKey) -> Self {
+                match key {
+                    $(AuxvKey::$name => $value,)*
+                    AuxvKey::Unknown(key) => key,
+                }
+            }
+        }
+    };
+}
+
+auxv_keys! {
+    /// `AT_IGNORE`: entry should be ignored
+    Ignore = 1,
+    /// `AT_EXECFD`: file descriptor of the program
+    ExecFd = 2,
+    /// `AT_PHDR`: address of the program headers of the executable
+    Phdr = 3,
+    /// `AT_PHENT`: size of a program header entry
+    Phent = 4,
+    /// `AT_PHNUM`: number of program headers
+    Phnum = 5,
+    /// `AT_PAGESZ`: system page size
+    PageSize = 6,
+    /// `AT_BASE`: base address of the program interpreter (usually the dynamic linker)
+    Base = 7,
+    /// `AT_FLAGS`: flags
+    Flags = 8,
+    /// `AT_ENTRY`: entry address of the executable
+    Entry = 9,
+    /// `AT_NOTELF`: the program is not ELF
+    NotElf = 10,
+    /// `AT_UID`: real user ID
+    Uid = 11,
+    /// `AT_EUID`: effective user ID
+    Euid = 12,
+    /// `AT_GID`: real group ID
+    Gid = 13,
+    /// `AT_EGID`: effective group ID
+    Egid = 14,
+    /// `AT_PLATFORM`: address of a string that identifies the hardware platform
+    Platform = 15,
+    /// `AT_HWCAP`: architecture-dependent hints about processor capabilities
+    HwCap = 16,
+    /// `AT_CLKTCK`: frequency of `times(2)`
+    ClockTick = 17,
+    /// `AT_SECURE`: the program is running in secure mode (such as setuid)
+    Secure = 23,
+    /// `AT_BASE_PLATFORM`: address of a string that identifies the real platform
+    BasePlatform = 24,
+    /// `AT_RANDOM`: address of 16 random bytes
+    Random = 25,
+    /// `AT_HWCAP2`: further architecture-dependent hints about processor capabilities
+    HwCap2 = 26,
+    /// `AT_RSEQ_FEATURE_SIZE`: size of the supported rseq features
+    RseqFeatureSize = 27,
+    /// `AT_RSEQ_ALIGN`: alignment of the rseq area
+    RseqAlign = 28,
+    /// `AT_HWCAP3`: further architecture-dependent hints about processor capabilities
+    HwCap3 = 29,
+    /// `AT_HWCAP4`: further architecture-dependent hints about processor capabilities
+    HwCap4 = 30,
+    /// `AT_EXECFN`: address of the pathname used to execute the program
+    ExecFn = 31,
+    /// `AT_SYSINFO`: entry point of the vsyscall page (32-bit x86 only)
+    Sysinfo = 32,
+    /// `AT_SYSINFO_EHDR`: address of the vDSO
+    SysinfoEhdr = 33,
+    /// `AT_MINSIGSTKSZ`: minimal stack size for signal delivery
+    MinSigStackSize = 51,
+}
+
+bitflags! {
+    /// The `AT_HWCAP` bits on x86 and x86_64
+    ///
+    /// These are the bits of the EDX register of CPUID leaf 1.
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct X86HwCap: u64 {
+        const FPU = 1 << 0;
+        const VME = 1 << 1;
+        const DE = 1 << 2;
+        const PSE = 1 << 3;
+        const TSC = 1 << 4;
+        const MSR = 1 << 5;
+        const PAE = 1 << 6;
+        const MCE = 1 << 7;
+        const CX8 = 1 << 8;
+        const APIC = 1 << 9;
+        const SEP = 1 << 11;
+        const MTRR = 1 << 12;
+        const PGE = 1 << 13;
+        const MCA = 1 << 14;
+        const CMOV = 1 << 15;
+        const PAT = 1 << 16;
+        const PSE36 = 1 << 17;
+        const PN = 1 << 18;
+        const CLFLUSH = 1 << 19;
+        const DS = 1 << 21;
+        const ACPI = 1 << 22;
+        const MMX = 1 << 23;
+        const FXSR = 1 << 24;
+        const SSE = 1 << 25;
+        const SSE2 = 1 << 26;
+        const SS = 1 << 27;
+        const HT = 1 << 28;
+        const TM = 1 << 29;
+        const IA64 = 1 << 30;
+        const PBE = 1 << 31;
+    }
+}
+
+bitflags! {
+    /// The `AT_HWCAP2` bits on x86 and x86_64
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct X86HwCap2: u64 {
+        /// `MONITOR` and `MWAIT` can be used from user space
+        const RING3MWAIT = 1 << 0;
+        /// The `FSGSBASE` instructions can be used
+        const FSGSBASE = 1 << 1;
+    }
+}
+
+bitflags! {
+    /// The `AT_HWCAP` bits on aarch64
+    ///
+    /// See `Documentation/arch/arm64/elf_hwcaps.rst` in the kernel sources.
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct Aarch64HwCap: u64 {
+        const FP = 1 << 0;
+        const ASIMD = 1 << 1;
+        const EVTSTRM = 1 << 2;
+        const AES = 1 << 3;
+        const PMULL = 1 << 4;
+        const SHA1 = 1 << 5;
+        const SHA2 = 1 << 6;
+        const CRC32 = 1 << 7;
+        const ATOMICS = 1 << 8;
+        const FPHP = 1 << 9;
+        const ASIMDHP = 1 << 10;
+        const CPUID = 1 << 11;
+        const ASIMDRDM = 1 << 12;
+        const JSCVT = 1 << 13;
+        const FCMA = 1 << 14;
+        const LRCPC = 1 << 15;
+        const DCPOP = 1 << 16;
+        const SHA3 = 1 << 17;
+        const SM3 = 1 << 18;
+        const SM4 = 1 << 19;
+        const ASIMDDP = 1 << 20;
+        const SHA512 = 1 << 21;
+        const SVE = 1 << 22;
+        const ASIMDFHM = 1 << 23;
+        const DIT = 1 << 24;
+        const USCAT = 1 << 25;
+        const ILRCPC = 1 << 26;
+        const FLAGM = 1 << 27;
+        const SSBS = 1 << 28;
+        const SB = 1 << 29;
+        const PACA = 1 << 30;
+        const PACG = 1 << 31;
+    }
+}
+
+bitflags! {
+    /// The `AT_HWCAP2` bits on aarch64
+    ///
+    /// See `Documentation/arch/arm64/elf_hwcaps.rst` in the kernel sources.
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct Aarch64HwCap2: u64 {
+        const DCPODP = 1 << 0;
+        const SVE2 = 1 << 1;
+        const SVEAES = 1 << 2;
+        const SVEPMULL = 1 << 3;
+        const SVEBITPERM = 1 << 4;
+        const SVESHA3 = 1 << 5;
+        const SVESM4 = 1 << 6;
+        const FLAGM2 = 1 << 7;
+        const FRINT = 1 << 8;
+        const SVEI8MM = 1 << 9;
+        const SVEF32MM = 1 << 10;
+        const SVEF64MM = 1 << 11;
+        const SVEBF16 = 1 << 12;
+        const I8MM = 1 << 13;
+        const BF16 = 1 << 14;
+        const DGH = 1 << 15;
+        const RNG = 1 << 16;
+        const BTI = 1 << 17;
+        const MTE = 1 << 18;
+    }
+}
+
+/// The auxiliary vector of a process, from the `/proc/<pid>/auxv` file
+///
+/// The values are the raw words passed by the kernel to the program when it was started.  Some of
+/// them are addresses in the memory of the process (such as [platform()](Auxv::platform) and
+/// [execfn()](Auxv::execfn)); reading the strings that they point to requires access to that
+/// memory.
+///
+/// The entries are parsed as native-endian words of the size of `usize`, so the file must come
+/// from a process with the same word size as this one.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Auxv {
+    /// The raw keys and values, in the order that they appear in the file
+    pub entries: Vec<(u64, u64)>,
+}
+
+impl crate::FromRead for Auxv {
+    fn from_read<R: Read>(mut r: R) -> ProcResult<Self> {
+        const WORD: usize = size_of::<usize>();
+
+        let mut buf = Vec::new();
+        r.read_to_end(&mut buf)?;
+
+        // some kernel processes won't have any data for their auxv file
+        let mut entries = Vec::new();
+        for entry in buf.chunks_exact(2 * WORD) {
+            let mut word = [0; WORD];
+            word.copy_from_slice(&entry[..WORD]);
+            let key = usize::from_ne_bytes(word) as u64;
+            word.copy_from_slice(&entry[WORD..]);
+            let value = usize::from_ne_bytes(word) as u64;
+            if key == 0 {
+                break;
+            }
+            entries.push((key, value));
+        }
+
+        Ok(Auxv { entries })
+    }
+}
+
+impl Auxv {
+    /// Returns the value for the given key
+    pub fn get(&self, key: AuxvKey) -> Option<u64> {
+        let key = u64::from(key);
+        self.entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+
+    /// Iterates over the keys and values
+    pub fn iter(&self) -> impl Iterator<Item = (AuxvKey, u64)> + '_ {
+        self.entries.iter().map(|(k, v)| (AuxvKey::from(*k), *v))
+    }
+
+    /// The system page size, in bytes
+    pub fn page_size(&self) -> Option<u64> {
+        self.get(AuxvKey::PageSize)
+    }
+
+    /// The number of clock ticks per second
+    pub fn clock_ticks(&self) -> Option<u64> {
+        self.get(AuxvKey::ClockTick)
+    }
+
+    /// The entry address of the executable
+    pub fn entry(&self) -> Option<u64> {
+        self.get(AuxvKey::Entry)
+    }
+
+    /// The base address of the program interpreter
+    ///
+    /// This is 0 for statically linked programs.
+    pub fn base(&self) -> Option<u64> {
+        self.get(AuxvKey::Base)
+    }
+
+    /// True if the program is running in secure mode, for example because it's setuid
+    pub fn secure(&self) -> Option<bool> {
+        self.get(AuxvKey::Secure).map(|v| v != 0)
+    }
+
+    /// The address of 16 random bytes, in the memory of the process
+    pub fn random(&self) -> Option<u64> {
+        self.get(AuxvKey::Random)
+    }
+
+    /// The address of the vDSO
+    pub fn vdso(&self) -> Option<u64> {
+        self.get(AuxvKey::SysinfoEhdr)
+    }
+
+    /// The address of the platform string, in the memory of the process
+    pub fn platform(&self) -> Option<u64> {
+        self.get(AuxvKey::Platform)
+    }
+
+    /// The address of the base platform string, in the memory of the process
+    pub fn base_platform(&self) -> Option<u64> {
+        self.get(AuxvKey::BasePlatform)
+    }
+
+    /// The address of the pathname used to execute the program, in the memory of the process
+    pub fn execfn(&self) -> Option<u64> {
+        self.get(AuxvKey::ExecFn)
+    }
+
+    /// The raw `AT_HWCAP` value
+    ///
+    /// The meaning of the bits depends on the architecture; see [x86_hwcap()](Auxv::x86_hwcap)
+    /// and [aarch64_hwcap()](Auxv::aarch64_hwcap).
+    pub fn hwcap(&self) -> Option<u64> {
+        self.get(AuxvKey::HwCap)
+    }
+
+    /// The raw `AT_HWCAP2` value
+    pub fn hwcap2(&self) -> Option<u64> {
+        self.get(AuxvKey::HwCap2)
+    }
+
+    /// The `AT_HWCAP` value of an x86 or x86_64 process
+    pub fn x86_hwcap(&self) -> Option<X86HwCap> {
+        self.hwcap().map(X86HwCap::from_bits_retain)
+    }
+
+    /// The `AT_HWCAP2` value of an x86 or x86_64 process
+    pub fn x86_hwcap2(&self) -> Option<X86HwCap2> {
+        self.hwcap2().map(X86HwCap2::from_bits_retain)
+    }
+
+    /// The `AT_HWCAP` value of an aarch64 process
+    pub fn aarch64_hwcap(&self) -> Option<Aarch64HwCap> {
+        self.hwcap().map(Aarch64HwCap::from_bits_retain)
+    }
+
+    /// The `AT_HWCAP2` value of an aarch64 process
+    pub fn aarch64_hwcap2(&self) -> Option<Aarch64HwCap2> {
+        self.hwcap2().map(Aarch64HwCap2::from_bits_retain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_auxv() {
+        let entries: &[(usize, usize)] = &[
+            (33, 0x7ffd1b3f5000),
+            (16, 0x178bfbff),
+            (6, 4096),
+            (17, 100),
+            (7, 0x7f2a4c1e2000),
+            (9, 0x55d0c2a3b0c0),
+            (23, 0),
+            (25, 0x7ffd1b3e1a49),
+            (26, 0x2),
+            (31, 0x7ffd1b3e2fe8),
+            (15, 0x7ffd1b3e1a59),
+            (99, 1),
+            (0, 0),
+        ];
+        let mut bytes = Vec::new();
+        for (k, v) in entries {
+            bytes.extend_from_slice(&k.to_ne_bytes());
+            bytes.extend_from_slice(&v.to_ne_bytes());
+        }
+
+        let auxv = Auxv::from_read(&bytes[..]).unwrap();
+        assert_eq!(auxv.entries.len(), 12);
+        assert_eq!(auxv.page_size(), Some(4096));
+        assert_eq!(auxv.clock_ticks(), Some(100));
+        assert_eq!(auxv.entry(), Some(0x55d0c2a3b0c0));
+        assert_eq!(auxv.base(), Some(0x7f2a4c1e2000));
+        assert_eq!(auxv.secure(), Some(false));
+        assert_eq!(auxv.random(), Some(0x7ffd1b3e1a49));
+        assert_eq!(auxv.vdso(), Some(0x7ffd1b3f5000));
+        assert_eq!(auxv.execfn(), Some(0x7ffd1b3e2fe8));
+        assert_eq!(auxv.base_platform(), None);
+
+        let hwcap = auxv.x86_hwcap().unwrap();
+        assert!(hwcap.contains(X86HwCap::FPU | X86HwCap::SSE | X86HwCap::SSE2));
+        assert_eq!(auxv.x86_hwcap2(), Some(X86HwCap2::FSGSBASE));
+
+        let keys: Vec<_> = auxv.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys[0], AuxvKey::SysinfoEhdr);
+        assert_eq!(keys[11], AuxvKey::Unknown(99));
+        assert_eq!(u64::from(AuxvKey::MinSigStackSize), 51);
+
+        assert!(Auxv::from_read(&[][..]).unwrap().entries.is_empty());
+    }
+}
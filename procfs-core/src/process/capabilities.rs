@@ -0,0 +1,158 @@
+use bitflags::bitflags;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+bitflags! {
+    /// A set of Linux capabilities
+    ///
+    /// See `capabilities(7)` for details about each capability.
+    ///
+    /// Capabilities that were added to the kernel after this list was written don't have a name
+    /// here, but are still preserved (see [Capabilities::unknown()]).
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct Capabilities: u64 {
+        /// Make arbitrary changes to file UIDs and GIDs
+        const CAP_CHOWN = 1 << 0;
+        /// Bypass file read, write, and execute permission checks
+        const CAP_DAC_OVERRIDE = 1 << 1;
+        /// Bypass file read permission checks and directory read and execute permission checks
+        const CAP_DAC_READ_SEARCH = 1 << 2;
+        /// Bypass permission checks on operations that normally require the filesystem UID of the
+        /// process to match the UID of the file
+        const CAP_FOWNER = 1 << 3;
+        /// Don't clear set-user-ID and set-group-ID mode bits when a file is modified
+        const CAP_FSETID = 1 << 4;
+        /// Bypass permission checks for sending signals
+        const CAP_KILL = 1 << 5;
+        /// Make arbitrary manipulations of process GIDs and supplementary GID list
+        const CAP_SETGID = 1 << 6;
+        /// Make arbitrary manipulations of process UIDs
+        const CAP_SETUID = 1 << 7;
+        /// Add or remove capabilities from the bounding set, and transfer capabilities
+        const CAP_SETPCAP = 1 << 8;
+        /// Set the `FS_APPEND_FL` and `FS_IMMUTABLE_FL` inode flags
+        const CAP_LINUX_IMMUTABLE = 1 << 9;
+        /// Bind a socket to Internet domain privileged ports (port numbers less than 1024)
+        const CAP_NET_BIND_SERVICE = 1 << 10;
+        /// (Unused) Make socket broadcasts, and listen to multicasts
+        const CAP_NET_BROADCAST = 1 << 11;
+        /// Perform various network-related operations
+        const CAP_NET_ADMIN = 1 << 12;
+        /// Use RAW and PACKET sockets
+        const CAP_NET_RAW = 1 << 13;
+        /// Lock memory
+        const CAP_IPC_LOCK = 1 << 14;
+        /// Bypass permission checks for operations on System V IPC objects
+        const CAP_IPC_OWNER = 1 << 15;
+        /// Load and unload kernel modules
+        const CAP_SYS_MODULE = 1 << 16;
+        /// Perform I/O port operations, and access `/proc/kcore`
+        const CAP_SYS_RAWIO = 1 << 17;
+        /// Use `chroot(2)`
+        const CAP_SYS_CHROOT = 1 << 18;
+        /// Trace arbitrary processes using `ptrace(2)`
+        const CAP_SYS_PTRACE = 1 << 19;
+        /// Use `acct(2)`
+        const CAP_SYS_PACCT = 1 << 20;
+        /// Perform a range of system administration operations
+        const CAP_SYS_ADMIN = 1 << 21;
+        /// Use `reboot(2)` and `kexec_load(2)`
+        const CAP_SYS_BOOT = 1 << 22;
+        /// Raise the nice value of processes, and change scheduling policies and CPU affinity
+        const CAP_SYS_NICE = 1 << 23;
+        /// Override resource limits and quotas
+        const CAP_SYS_RESOURCE = 1 << 24;
+        /// Set the system clock and the real-time (hardware) clock
+        const CAP_SYS_TIME = 1 << 25;
+        /// Use `vhangup(2)` and privileged `ioctl(2)` operations on virtual terminals
+        const CAP_SYS_TTY_CONFIG = 1 << 26;
+        /// Create special files using `mknod(2)`
+        const CAP_MKNOD = 1 << 27;
+        /// Establish leases on arbitrary files
+        const CAP_LEASE = 1 << 28;
+        /// Write records to the kernel auditing log
+        const CAP_AUDIT_WRITE = 1 << 29;
+        /// Configure kernel auditing
+        const CAP_AUDIT_CONTROL = 1 << 30;
+        /// Set arbitrary capabilities on a file
+        const CAP_SETFCAP = 1 << 31;
+        /// Override Mandatory Access Control (implemented for the Smack LSM)
+        const CAP_MAC_OVERRIDE = 1 << 32;
+        /// Allow MAC configuration or state changes (implemented for the Smack LSM)
+        const CAP_MAC_ADMIN = 1 << 33;
+        /// Perform privileged `syslog(2)` operations
+        const CAP_SYSLOG = 1 << 34;
+        /// Trigger something that will wake up the system
+        const CAP_WAKE_ALARM = 1 << 35;
+        /// Employ features that can block system suspend
+        const CAP_BLOCK_SUSPEND = 1 << 36;
+        /// Allow reading the audit log via a multicast netlink socket
+        const CAP_AUDIT_READ = 1 << 37;
+        /// Employ various performance-monitoring mechanisms
+        ///
+        /// (since Linux 5.8)
+        const CAP_PERFMON = 1 << 38;
+        /// Employ privileged BPF operations
+        ///
+        /// (since Linux 5.8)
+        const CAP_BPF = 1 << 39;
+        /// Perform checkpoint/restore related operations
+        ///
+        /// (since Linux 5.9)
+        const CAP_CHECKPOINT_RESTORE = 1 << 40;
+    }
+}
+
+impl Capabilities {
+    /// Returns all capabilities that are supported by a kernel whose highest capability number is
+    /// `last_cap`
+    ///
+    /// The value of `last_cap` can be read from `/proc/sys/kernel/cap_last_cap`.  The result may
+    /// contain bits that don't have a name in this crate.
+    pub fn supported(last_cap: u32) -> Capabilities {
+        let bits = if last_cap >= 63 {
+            u64::MAX
+        } else {
+            (1u64 << (last_cap + 1)) - 1
+        };
+        Capabilities::from_bits_retain(bits)
+    }
+
+    /// Returns the bits in this set that don't correspond to a named capability
+    ///
+    /// These are usually capabilities that were added to the kernel after this version of this
+    /// crate was released.
+    pub fn unknown(&self) -> u64 {
+        self.bits() & !Capabilities::all().bits()
+    }
+
+    /// Returns the numbers of the capabilities in this set, including unknown capabilities
+    pub fn numbers(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..64).filter(move |n| self.bits() & (1 << n) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let caps = Capabilities::from_bits_retain(0x000001ffffffffff);
+        assert_eq!(caps, Capabilities::all());
+        assert_eq!(caps.unknown(), 0);
+        assert!(caps.contains(Capabilities::CAP_SYS_ADMIN | Capabilities::CAP_BPF));
+        assert_eq!(Capabilities::supported(40), Capabilities::all());
+
+        let caps = Capabilities::from_bits_retain(0x0000_8000_0020_0001);
+        assert_eq!(caps.unknown(), 0x0000_8000_0000_0000);
+        assert_eq!(caps.numbers().collect::<Vec<_>>(), vec![0, 21, 47]);
+        let names: Vec<_> = caps.iter_names().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["CAP_CHOWN", "CAP_SYS_ADMIN"]);
+
+        assert_eq!(Capabilities::supported(63).bits(), u64::MAX);
+        assert_eq!(Capabilities::supported(42).unknown(), 0b11 << 41);
+    }
+}
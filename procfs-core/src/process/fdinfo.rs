@@ -0,0 +1,569 @@
+use crate::{Lock, ProcResult};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Information about an open file descriptor, from the `/proc/<pid>/fdinfo/<fd>` file.
+///
+/// Every fd has a position and open flags.  Depending on what kind of file the fd refers to,
+/// the kernel may also print some extra type-specific information, which is available in
+/// the [`kind`](FDDetails::kind) field.
+///
+/// (since Linux 2.6.22)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct FDDetails {
+    /// The current file offset, in bytes
+    pub pos: u64,
+    /// The file access mode and status flags (`O_RDONLY`, `O_CLOEXEC`, etc)
+    ///
+    /// See `open(2)` for details.  Note that the exact values of these flags can differ
+    /// between architectures.
+    pub flags: u32,
+    /// The ID of the mount containing this file
+    ///
+    /// This can be matched against the `mnt_id` field of [`MountInfo`](crate::process::MountInfo).
+    ///
+    /// (since Linux 3.15)
+    pub mnt_id: Option<u64>,
+    /// The inode number of this file
+    ///
+    /// (since Linux 5.12)
+    pub ino: Option<u64>,
+    /// Any extra information that is specific to the kind of file this fd refers to
+    pub kind: FDDetailsKind,
+    /// File locks held by this fd
+    ///
+    /// (since Linux 4.1)
+    pub locks: Vec<Lock>,
+}
+
+/// Type-specific information about a file descriptor
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum FDDetailsKind {
+    /// No type-specific information was found (this is the case for regular files, pipes, sockets, etc)
+    Generic,
+    /// An eventfd (see `eventfd(2)`)
+    EventFd(EventFdInfo),
+    /// An epoll instance (see `epoll(7)`), with one entry per file descriptor in the interest list
+    Epoll(Vec<EpollTarget>),
+    /// An inotify instance (see `inotify(7)`), with one entry per watch
+    Inotify(Vec<InotifyWatch>),
+    /// A fanotify instance (see `fanotify(7)`)
+    Fanotify(FanotifyInfo),
+    /// A timerfd (see `timerfd_create(2)`)
+    TimerFd(TimerFdInfo),
+    /// A signalfd (see `signalfd(2)`)
+    SignalFd {
+        /// A bitmask of the signals that this fd is accepting
+        ///
+        /// Bit 0 corresponds to signal 1, bit 1 to signal 2, and so on.
+        sigmask: u64,
+    },
+    /// A pidfd (see `pidfd_open(2)`)
+    PidFd {
+        /// The PID of the process referred to by the pidfd
+        ///
+        /// This is `0` if the process is in a PID namespace that is not visible from the reader's
+        /// PID namespace, and `-1` if the process has already exited.
+        pid: i32,
+        /// The PIDs of the process in each nested PID namespace
+        ///
+        /// (since Linux 5.10)
+        nspid: Option<Vec<i32>>,
+    },
+}
+
+/// Information about an eventfd
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct EventFdInfo {
+    /// The current value of the counter
+    pub count: u64,
+    /// A unique ID for this eventfd
+    ///
+    /// (since Linux 5.2)
+    pub id: Option<u32>,
+    /// Whether this eventfd was created with `EFD_SEMAPHORE`
+    ///
+    /// (since Linux 6.6)
+    pub semaphore: Option<bool>,
+}
+
+/// A file descriptor that is being monitored by an epoll instance
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct EpollTarget {
+    /// The number of the target file descriptor
+    pub tfd: i32,
+    /// The event mask being monitored (`EPOLLIN`, `EPOLLOUT`, etc)
+    pub events: u32,
+    /// The user data associated with this file descriptor
+    pub data: u64,
+    /// The file offset of the target file
+    ///
+    /// (since Linux 3.19)
+    pub pos: Option<i64>,
+    /// The inode number of the target file
+    ///
+    /// (since Linux 3.19)
+    pub ino: Option<u64>,
+    /// The device ID of the filesystem containing the target file
+    ///
+    /// (since Linux 3.19)
+    pub sdev: Option<u32>,
+}
+
+/// An opaque handle that identifies a file, as would be returned by `name_to_handle_at(2)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct FileHandle {
+    /// The type of handle
+    pub handle_type: i32,
+    /// The handle itself
+    pub handle: Vec<u8>,
+}
+
+/// A watch in an inotify instance
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct InotifyWatch {
+    /// The watch descriptor
+    pub wd: i32,
+    /// The inode number of the target file
+    pub ino: u64,
+    /// The device ID of the filesystem containing the target file
+    pub sdev: u32,
+    /// The event mask being monitored
+    pub mask: u32,
+    /// The mask of events being ignored
+    pub ignored_mask: u32,
+    /// A handle for the target file
+    ///
+    /// Only present if the kernel was built with `CONFIG_EXPORTFS`.
+    pub handle: Option<FileHandle>,
+}
+
+/// Information about a fanotify instance
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct FanotifyInfo {
+    /// The flags that were passed to `fanotify_init(2)`
+    pub flags: u32,
+    /// The event file status flags that were passed to `fanotify_init(2)`
+    pub event_flags: u32,
+    /// One entry per mark
+    pub marks: Vec<FanotifyMark>,
+}
+
+/// A mark in a fanotify instance
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct FanotifyMark {
+    /// The object being marked
+    pub target: FanotifyMarkTarget,
+    /// Internal flags for this mark
+    pub mflags: u32,
+    /// The event mask being monitored
+    pub mask: u32,
+    /// The mask of events being ignored
+    pub ignored_mask: u32,
+}
+
+/// The object that a fanotify mark applies to
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub enum FanotifyMarkTarget {
+    /// A single inode
+    Inode {
+        /// The inode number
+        ino: u64,
+        /// The device ID of the filesystem containing the inode
+        sdev: u32,
+        /// A handle for the target file
+        ///
+        /// Only present if the kernel was built with `CONFIG_EXPORTFS`.
+        handle: Option<FileHandle>,
+    },
+    /// A mount
+    Mount {
+        /// The mount ID
+        mnt_id: u32,
+    },
+    /// An entire filesystem
+    Filesystem {
+        /// The device ID of the filesystem
+        sdev: u32,
+    },
+}
+
+/// Information about a timerfd
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct TimerFdInfo {
+    /// The clock used to mark the progress of the timer
+    ///
+    /// This is the raw clock ID, such as `CLOCK_REALTIME` (0) or `CLOCK_MONOTONIC` (1)
+    pub clockid: i32,
+    /// The number of timer expirations that have occurred, but not yet been read
+    pub ticks: u64,
+    /// The flags that were used to arm the timer (for example `TFD_TIMER_ABSTIME`)
+    pub settime_flags: u32,
+    /// The time until the next expiration, as (seconds, nanoseconds)
+    pub it_value: (u64, u64),
+    /// The interval of the timer, as (seconds, nanoseconds)
+    pub it_interval: (u64, u64),
+}
+
+/// Splits a line like `wd:3 ino:9e7e mask:800afce` or `tfd:    5 events:    1d` into key-value pairs
+fn split_pairs(line: &str) -> HashMap<&str, &str> {
+    let mut map = HashMap::new();
+    let mut tokens = line.split_whitespace().peekable();
+    while let Some(tok) = tokens.next() {
+        if let Some((key, value)) = tok.split_once(':') {
+            let value = if value.is_empty() {
+                match tokens.peek() {
+                    Some(next) if !next.contains(':') => tokens.next().unwrap_or_default(),
+                    _ => value,
+                }
+            } else {
+                value
+            };
+            map.insert(key, value);
+        }
+    }
+    map
+}
+
+fn parse_file_handle(map: &HashMap<&str, &str>) -> ProcResult<Option<FileHandle>> {
+    let (Some(handle_type), Some(hex)) = (map.get("fhandle-type"), map.get("f_handle")) else {
+        return Ok(None);
+    };
+    let mut handle = Vec::with_capacity(hex.len() / 2);
+    for i in (0..hex.len()).step_by(2) {
+        handle.push(from_str!(u8, expect!(hex.get(i..i + 2)), 16));
+    }
+    Ok(Some(FileHandle {
+        handle_type: from_str!(i32, handle_type, 16),
+        handle,
+    }))
+}
+
+fn parse_time_pair(s: &str) -> ProcResult<(u64, u64)> {
+    let s = s.trim().trim_start_matches('(').trim_end_matches(')');
+    let (secs, nsecs) = expect!(s.split_once(','));
+    Ok((from_str!(u64, secs.trim()), from_str!(u64, nsecs.trim())))
+}
+
+impl crate::FromBufRead for FDDetails {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut pos = None;
+        let mut flags = None;
+        let mut mnt_id = None;
+        let mut ino = None;
+        let mut locks = Vec::new();
+
+        let mut eventfd: Option<EventFdInfo> = None;
+        let mut epoll = Vec::new();
+        let mut inotify = Vec::new();
+        let mut fanotify: Option<FanotifyInfo> = None;
+        let mut timerfd: HashMap<String, String> = HashMap::new();
+        let mut sigmask = None;
+        let mut pidfd: Option<(i32, Option<Vec<i32>>)> = None;
+
+        for line in r.lines() {
+            let line = line?;
+
+            if let Some(rest) = line.strip_prefix("inotify ") {
+                let map = split_pairs(rest);
+                inotify.push(InotifyWatch {
+                    wd: from_str!(i32, expect!(map.get("wd")), 16),
+                    ino: from_str!(u64, expect!(map.get("ino")), 16),
+                    sdev: from_str!(u32, expect!(map.get("sdev")), 16),
+                    mask: from_str!(u32, expect!(map.get("mask")), 16),
+                    ignored_mask: from_str!(u32, expect!(map.get("ignored_mask")), 16),
+                    handle: parse_file_handle(&map)?,
+                });
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("fanotify ") {
+                let map = split_pairs(rest);
+                let info = fanotify.get_or_insert_with(|| FanotifyInfo {
+                    flags: 0,
+                    event_flags: 0,
+                    marks: Vec::new(),
+                });
+                if let Some(f) = map.get("flags") {
+                    info.flags = from_str!(u32, f, 16);
+                    info.event_flags = from_str!(u32, expect!(map.get("event-flags")), 16);
+                    continue;
+                }
+                let target = if let Some(ino) = map.get("ino") {
+                    FanotifyMarkTarget::Inode {
+                        ino: from_str!(u64, ino, 16),
+                        sdev: from_str!(u32, expect!(map.get("sdev")), 16),
+                        handle: parse_file_handle(&map)?,
+                    }
+                } else if let Some(mnt_id) = map.get("mnt_id") {
+                    FanotifyMarkTarget::Mount {
+                        mnt_id: from_str!(u32, mnt_id, 16),
+                    }
+                } else {
+                    FanotifyMarkTarget::Filesystem {
+                        sdev: from_str!(u32, expect!(map.get("sdev")), 16),
+                    }
+                };
+                info.marks.push(FanotifyMark {
+                    target,
+                    mflags: from_str!(u32, expect!(map.get("mflags")), 16),
+                    mask: from_str!(u32, expect!(map.get("mask")), 16),
+                    ignored_mask: from_str!(u32, expect!(map.get("ignored_mask")), 16),
+                });
+                continue;
+            }
+            if line.starts_with("tfd:") {
+                let map = split_pairs(&line);
+                epoll.push(EpollTarget {
+                    tfd: from_str!(i32, expect!(map.get("tfd"))),
+                    events: from_str!(u32, expect!(map.get("events")), 16),
+                    data: from_str!(u64, expect!(map.get("data")), 16),
+                    pos: match map.get("pos") {
+                        Some(p) => Some(from_str!(i64, p)),
+                        None => None,
+                    },
+                    ino: match map.get("ino") {
+                        Some(i) => Some(from_str!(u64, i, 16)),
+                        None => None,
+                    },
+                    sdev: match map.get("sdev") {
+                        Some(s) => Some(from_str!(u32, s, 16)),
+                        None => None,
+                    },
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "pos" => pos = Some(from_str!(u64, value)),
+                "flags" => flags = Some(from_str!(u32, value, 8)),
+                "mnt_id" => mnt_id = Some(from_str!(u64, value)),
+                "ino" => ino = Some(from_str!(u64, value)),
+                "lock" => locks.push(Lock::from_line(value)?),
+                "eventfd-count" => {
+                    eventfd = Some(EventFdInfo {
+                        count: from_str!(u64, value, 16),
+                        id: None,
+                        semaphore: None,
+                    })
+                }
+                "eventfd-id" => {
+                    if let Some(e) = eventfd.as_mut() {
+                        e.id = Some(from_str!(u32, value));
+                    }
+                }
+                "eventfd-semaphore" => {
+                    if let Some(e) = eventfd.as_mut() {
+                        e.semaphore = Some(value != "0");
+                    }
+                }
+                "clockid" | "ticks" | "settime flags" | "it_value" | "it_interval" => {
+                    timerfd.insert(key.to_string(), value.to_string());
+                }
+                "sigmask" => sigmask = Some(from_str!(u64, value, 16)),
+                "Pid" => pidfd = Some((from_str!(i32, value), None)),
+                "NSpid" => {
+                    if let Some((_, nspid)) = pidfd.as_mut() {
+                        let mut v = Vec::new();
+                        for p in value.split_whitespace() {
+                            v.push(from_str!(i32, p));
+                        }
+                        *nspid = Some(v);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let kind = if let Some(eventfd) = eventfd {
+            FDDetailsKind::EventFd(eventfd)
+        } else if !epoll.is_empty() {
+            FDDetailsKind::Epoll(epoll)
+        } else if !inotify.is_empty() {
+            FDDetailsKind::Inotify(inotify)
+        } else if let Some(fanotify) = fanotify {
+            FDDetailsKind::Fanotify(fanotify)
+        } else if timerfd.contains_key("clockid") {
+            FDDetailsKind::TimerFd(TimerFdInfo {
+                clockid: from_str!(i32, expect!(timerfd.get("clockid"))),
+                ticks: from_str!(u64, expect!(timerfd.get("ticks"))),
+                settime_flags: from_str!(u32, expect!(timerfd.get("settime flags")), 8),
+                it_value: parse_time_pair(expect!(timerfd.get("it_value")))?,
+                it_interval: parse_time_pair(expect!(timerfd.get("it_interval")))?,
+            })
+        } else if let Some(sigmask) = sigmask {
+            FDDetailsKind::SignalFd { sigmask }
+        } else if let Some((pid, nspid)) = pidfd {
+            FDDetailsKind::PidFd { pid, nspid }
+        } else {
+            FDDetailsKind::Generic
+        };
+
+        Ok(FDDetails {
+            pos: expect!(pos),
+            flags: expect!(flags),
+            mnt_id,
+            ino,
+            kind,
+            locks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_fdinfo_regular() {
+        let data = r#"pos:	0
+flags:	0100002
+mnt_id:	19
+ino:	63107
+lock:	1: FLOCK  ADVISORY  WRITE 359 00:13:11691 0 EOF
+"#;
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        assert_eq!(info.pos, 0);
+        assert_eq!(info.flags, 0o100002);
+        assert_eq!(info.mnt_id, Some(19));
+        assert_eq!(info.ino, Some(63107));
+        assert!(matches!(info.kind, FDDetailsKind::Generic));
+        assert_eq!(info.locks.len(), 1);
+        assert_eq!(info.locks[0].pid, Some(359));
+        assert_eq!(info.locks[0].inode, 11691);
+    }
+
+    #[test]
+    fn test_fdinfo_eventfd() {
+        let data = r#"pos:	0
+flags:	02
+mnt_id:	10
+ino:	1057
+eventfd-count:               5a
+eventfd-id: 1
+eventfd-semaphore: 0
+"#;
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        let FDDetailsKind::EventFd(e) = info.kind else { panic!() };
+        assert_eq!(e.count, 0x5a);
+        assert_eq!(e.id, Some(1));
+        assert_eq!(e.semaphore, Some(false));
+    }
+
+    #[test]
+    fn test_fdinfo_epoll() {
+        let data = r#"pos:	0
+flags:	02
+mnt_id:	9
+tfd:        5 events:       1d data: ffffffffffffffff  pos:0 ino:61af sdev:7
+tfd:        8 events:       19 data:                8  pos:0 ino:1ec4 sdev:8
+"#;
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        let FDDetailsKind::Epoll(targets) = info.kind else {
+            panic!()
+        };
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].tfd, 5);
+        assert_eq!(targets[0].events, 0x1d);
+        assert_eq!(targets[0].data, u64::MAX);
+        assert_eq!(targets[1].data, 8);
+        assert_eq!(targets[1].ino, Some(0x1ec4));
+    }
+
+    #[test]
+    fn test_fdinfo_inotify() {
+        let data = r#"pos:	0
+flags:	02000000
+mnt_id:	9
+inotify wd:3 ino:9e7e sdev:800013 mask:800afce ignored_mask:0 fhandle-bytes:8 fhandle-type:1 f_handle:7e9e0000640d1b6d
+"#;
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        let FDDetailsKind::Inotify(watches) = info.kind else {
+            panic!()
+        };
+        assert_eq!(watches[0].wd, 3);
+        assert_eq!(watches[0].mask, 0x800afce);
+        let handle = watches[0].handle.as_ref().unwrap();
+        assert_eq!(handle.handle_type, 1);
+        assert_eq!(handle.handle, vec![0x7e, 0x9e, 0, 0, 0x64, 0x0d, 0x1b, 0x6d]);
+    }
+
+    #[test]
+    fn test_fdinfo_fanotify() {
+        let data = r#"pos:	0
+flags:	02
+mnt_id:	9
+fanotify flags:10 event-flags:0
+fanotify mnt_id:12 mflags:40 mask:38 ignored_mask:40000003
+fanotify ino:4f969 sdev:800013 mflags:0 mask:3b ignored_mask:40000000 fhandle-bytes:8 fhandle-type:1 f_handle:69f90400c275b5b4
+"#;
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        let FDDetailsKind::Fanotify(fan) = info.kind else {
+            panic!()
+        };
+        assert_eq!(fan.flags, 0x10);
+        assert_eq!(fan.marks.len(), 2);
+        assert!(matches!(
+            fan.marks[0].target,
+            FanotifyMarkTarget::Mount { mnt_id: 0x12 }
+        ));
+        assert!(matches!(
+            fan.marks[1].target,
+            FanotifyMarkTarget::Inode { ino: 0x4f969, .. }
+        ));
+    }
+
+    #[test]
+    fn test_fdinfo_timerfd() {
+        let data = r#"pos:	0
+flags:	02
+mnt_id:	9
+clockid: 0
+ticks: 0
+settime flags: 01
+it_value: (0, 49406829)
+it_interval: (1, 0)
+"#;
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        let FDDetailsKind::TimerFd(timer) = info.kind else {
+            panic!()
+        };
+        assert_eq!(timer.settime_flags, 1);
+        assert_eq!(timer.it_value, (0, 49406829));
+        assert_eq!(timer.it_interval, (1, 0));
+    }
+
+    #[test]
+    fn test_fdinfo_signalfd() {
+        let data = "pos:\t0\nflags:\t04002\nmnt_id:\t9\nsigmask:\t0000000000000200\n";
+        let info = FDDetails::from_read(data.as_bytes()).unwrap();
+        assert!(matches!(info.kind, FDDetailsKind::SignalFd { sigmask: 0x200 }));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{ProcError, ProcResult};
+use std::fmt;
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of lines that can be written to an ID map file (since Linux 4.15)
+///
+/// Before Linux 4.15 the limit was 5 lines.
+pub const ID_MAP_MAX_RANGES: usize = 340;
+
+/// A range of contiguous IDs that are mapped between a user namespace and its parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct IdMapRange {
+    /// The start of the range of IDs in the user namespace
+    pub inside: u32,
+    /// The start of the range of IDs in the user namespace of the process that opened the map file
+    /// (usually the parent user namespace)
+    pub outside: u32,
+    /// The number of IDs in the range
+    pub count: u32,
+}
+
+impl IdMapRange {
+    /// Creates a new range of `count` IDs, starting at `inside` in the user namespace and at
+    /// `outside` in the parent user namespace
+    pub fn new(inside: u32, outside: u32, count: u32) -> IdMapRange {
+        IdMapRange { inside, outside, count }
+    }
+
+    /// Maps an ID from the user namespace to the parent user namespace, if it's in this range
+    pub fn to_outside(&self, id: u32) -> Option<u32> {
+        let offset = id.checked_sub(self.inside)?;
+        if offset < self.count {
+            self.outside.checked_add(offset)
+        } else {
+            None
+        }
+    }
+
+    /// Maps an ID from the parent user namespace to the user namespace, if it's in this range
+    pub fn to_inside(&self, id: u32) -> Option<u32> {
+        let offset = id.checked_sub(self.outside)?;
+        if offset < self.count {
+            self.inside.checked_add(offset)
+        } else {
+            None
+        }
+    }
+}
+
+/// A user or group ID mapping for a user namespace
+///
+/// This is the contents of the `/proc/<pid>/uid_map`, `/proc/<pid>/gid_map` and
+/// `/proc/<pid>/projid_map` files.  See `user_namespaces(7)` for details.
+///
+/// The [Display](fmt::Display) implementation of this type produces the format that is expected
+/// when writing a map file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct IdMap(pub Vec<IdMapRange>);
+
+impl crate::FromBufRead for IdMap {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut v = Vec::new();
+        for line in r.lines() {
+            let line = line?;
+            let mut s = line.split_whitespace();
+            let Some(inside) = s.next() else {
+                continue;
+            };
+            v.push(IdMapRange {
+                inside: from_str!(u32, inside),
+                outside: from_str!(u32, expect!(s.next())),
+                count: from_str!(u32, expect!(s.next())),
+            });
+        }
+        Ok(IdMap(v))
+    }
+}
+
+impl fmt::Display for IdMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for range in &self.0 {
+            writeln!(f, "{} {} {}", range.inside, range.outside, range.count)?;
+        }
+        Ok(())
+    }
+}
+
+impl IdMap {
+    /// Return an iterator over [IdMapRange].
+    pub fn iter(&self) -> std::slice::Iter<'_, IdMapRange> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no ranges in this map
+    ///
+    /// A user namespace with an empty map has not yet had its map written.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Maps an ID from the user namespace to the parent user namespace
+    ///
+    /// Returns `None` if the ID is not mapped.  (The kernel will report such IDs as the overflow
+    /// ID, usually 65534.)
+    pub fn to_outside(&self, id: u32) -> Option<u32> {
+        self.0.iter().find_map(|r| r.to_outside(id))
+    }
+
+    /// Maps an ID from the parent user namespace to the user namespace
+    ///
+    /// Returns `None` if the ID is not mapped.
+    pub fn to_inside(&self, id: u32) -> Option<u32> {
+        self.0.iter().find_map(|r| r.to_inside(id))
+    }
+
+    /// Checks that this map would be accepted by the kernel
+    ///
+    /// The rules are described in `user_namespaces(7)`: there must be at least one and at most
+    /// [ID_MAP_MAX_RANGES] ranges, every range must have a non-zero count that doesn't overflow,
+    /// and ranges may not overlap with each other on either the inside or the outside.
+    pub fn validate(&self) -> ProcResult<()> {
+        if self.0.is_empty() {
+            return Err(ProcError::Other("ID map must contain at least one range".to_string()));
+        }
+        if self.0.len() > ID_MAP_MAX_RANGES {
+            return Err(ProcError::Other(format!(
+                "ID map contains {} ranges, but at most {} are allowed",
+                self.0.len(),
+                ID_MAP_MAX_RANGES
+            )));
+        }
+        for (idx, range) in self.0.iter().enumerate() {
+            if range.count == 0 {
+                return Err(ProcError::Other(format!("ID map range {:?} has a count of 0", range)));
+            }
+            if range.inside.checked_add(range.count - 1).is_none()
+                || range.outside.checked_add(range.count - 1).is_none()
+            {
+                return Err(ProcError::Other(format!("ID map range {:?} overflows", range)));
+            }
+            for other in &self.0[..idx] {
+                // `a` is the start of `other`, and `b` is the start of `range`
+                let overlaps = |a: u32, b: u32| a <= b + (range.count - 1) && b <= a + (other.count - 1);
+                if overlaps(other.inside, range.inside) || overlaps(other.outside, range.outside) {
+                    return Err(ProcError::Other(format!(
+                        "ID map ranges {:?} and {:?} overlap",
+                        other, range
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a IdMap {
+    type IntoIter = std::slice::Iter<'a, IdMapRange>;
+    type Item = &'a IdMapRange;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for IdMap {
+    type IntoIter = std::vec::IntoIter<IdMapRange>;
+    type Item = IdMapRange;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Whether the `setgroups(2)` system call is permitted in a user namespace
+///
+/// This is the contents of the `/proc/<pid>/setgroups` file.
+///
+/// (since Linux 3.19)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub enum SetGroups {
+    /// Processes in the user namespace that have the `CAP_SETGID` capability may call `setgroups(2)`
+    Allow,
+    /// `setgroups(2)` is not permitted in the user namespace
+    Deny,
+}
+
+impl fmt::Display for SetGroups {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SetGroups::Allow => "allow",
+                SetGroups::Deny => "deny",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for SetGroups {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "allow" => Ok(SetGroups::Allow),
+            "deny" => Ok(SetGroups::Deny),
+            _ => Err("Unknown setgroups value"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_id_map() {
+        let data = "         0       1000          1\n         1     100000      65536\n";
+        let map = IdMap::from_read(data.as_bytes()).unwrap();
+        assert_eq!(
+            map.0,
+            vec![IdMapRange::new(0, 1000, 1), IdMapRange::new(1, 100000, 65536)]
+        );
+        assert_eq!(map.to_string(), "0 1000 1\n1 100000 65536\n");
+        map.validate().unwrap();
+
+        assert_eq!(map.to_outside(0), Some(1000));
+        assert_eq!(map.to_outside(1), Some(100000));
+        assert_eq!(map.to_outside(65536), Some(165535));
+        assert_eq!(map.to_outside(65537), None);
+        assert_eq!(map.to_inside(1000), Some(0));
+        assert_eq!(map.to_inside(100001), Some(2));
+        assert_eq!(map.to_inside(999), None);
+
+        // the initial user namespace has an identity map of all IDs
+        let init = IdMap::from_read("         0          0 4294967295\n".as_bytes()).unwrap();
+        init.validate().unwrap();
+        assert_eq!(init.to_outside(12345), Some(12345));
+
+        assert!(IdMap(vec![]).validate().is_err());
+        assert!(IdMap(vec![IdMapRange::new(0, 0, 0)]).validate().is_err());
+        assert!(IdMap(vec![IdMapRange::new(u32::MAX, 0, 2)]).validate().is_err());
+        assert!(IdMap(vec![IdMapRange::new(0, 1000, 10), IdMapRange::new(9, 2000, 1)])
+            .validate()
+            .is_err());
+        assert!(IdMap(vec![IdMapRange::new(0, 1000, 10), IdMapRange::new(10, 1005, 1)])
+            .validate()
+            .is_err());
+        IdMap(vec![IdMapRange::new(0, 1000, 10), IdMapRange::new(10, 1010, 1)])
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_setgroups() {
+        assert_eq!("allow\n".parse::<SetGroups>().unwrap(), SetGroups::Allow);
+        assert_eq!("deny".parse::<SetGroups>().unwrap(), SetGroups::Deny);
+        assert_eq!(SetGroups::Deny.to_string(), "deny");
+    }
+}
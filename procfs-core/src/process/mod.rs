@@ -27,9 +27,15 @@ pub use namespaces::*;
 mod status;
 pub use status::*;
 
+mod sched;
+pub use sched::*;
+
 mod schedstat;
 pub use schedstat::*;
 
+mod smaps;
+pub use smaps::*;
+
 mod smaps_rollup;
 pub use smaps_rollup::*;
 
@@ -42,6 +48,30 @@ pub use pagemap::*;
 mod clear_refs;
 pub use clear_refs::*;
 
+mod fdinfo;
+pub use fdinfo::*;
+
+mod numa_maps;
+pub use numa_maps::*;
+
+mod id_map;
+pub use id_map::*;
+
+mod stack;
+pub use stack::*;
+
+mod timers;
+pub use timers::*;
+
+mod attr;
+pub use attr::*;
+
+mod capabilities;
+pub use capabilities::*;
+
+mod auxv;
+pub use auxv::*;
+
 bitflags! {
     /// Kernel flags for a process
     ///
@@ -0,0 +1,400 @@
+use super::{MMapPath, MemoryMaps};
+use crate::ProcResult;
+use bitflags::bitflags;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// The mode of a NUMA memory policy
+///
+/// See `set_mempolicy(2)` for details.
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum MemPolicyMode {
+    /// Use the process policy, or the system default policy
+    Default,
+    /// Allocate from the preferred node, falling back to other nodes
+    Preferred,
+    /// Allocate only from the given nodes
+    Bind,
+    /// Interleave allocations across the given nodes
+    Interleave,
+    /// Interleave allocations across the given nodes, using per-node weights
+    ///
+    /// (since Linux 6.9)
+    WeightedInterleave,
+    /// Allocate on the node of the CPU that triggered the allocation
+    Local,
+    /// Allocate from the preferred nodes, falling back to other nodes
+    ///
+    /// (since Linux 5.15)
+    PreferredMany,
+    /// Some other unknown mode
+    Other(String),
+}
+
+impl From<&str> for MemPolicyMode {
+    fn from(s: &str) -> MemPolicyMode {
+        match s {
+            "default" => MemPolicyMode::Default,
+            "prefer" => MemPolicyMode::Preferred,
+            "bind" => MemPolicyMode::Bind,
+            "interleave" => MemPolicyMode::Interleave,
+            "weighted interleave" => MemPolicyMode::WeightedInterleave,
+            "local" => MemPolicyMode::Local,
+            "prefer (many)" => MemPolicyMode::PreferredMany,
+            x => MemPolicyMode::Other(x.to_string()),
+        }
+    }
+}
+
+bitflags! {
+    /// Optional mode flags of a NUMA memory policy
+    ///
+    /// The values are the same as the flags that `set_mempolicy(2)` takes with the mode.
+    // source: include/uapi/linux/mempolicy.h
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct MemPolicyFlags: u32 {
+        /// The nodemask is not remapped when the allowed nodes of the process change (`MPOL_F_STATIC_NODES`)
+        const STATIC_NODES = 1 << 15;
+        /// The nodemask is relative to the allowed nodes of the process (`MPOL_F_RELATIVE_NODES`)
+        const RELATIVE_NODES = 1 << 14;
+        /// NUMA balancing is enabled for this policy (`MPOL_F_NUMA_BALANCING`)
+        const NUMA_BALANCING = 1 << 13;
+    }
+}
+
+/// A NUMA memory policy, as displayed in `/proc/<pid>/numa_maps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct MemPolicy {
+    /// The policy mode
+    pub mode: MemPolicyMode,
+    /// Any optional mode flags
+    pub flags: MemPolicyFlags,
+    /// The nodes that this policy applies to
+    ///
+    /// This is empty for policies (like `default` and `local`) that don't take a set of nodes.
+    pub nodes: Vec<u32>,
+}
+
+impl MemPolicy {
+    fn from_str(s: &str) -> ProcResult<MemPolicy> {
+        let (policy, nodes) = match s.split_once(':') {
+            Some((policy, nodes)) => (policy, Some(nodes)),
+            None => (s, None),
+        };
+        let (mode, flag_str) = match policy.split_once('=') {
+            Some((mode, flags)) => (mode, Some(flags)),
+            None => (policy, None),
+        };
+
+        let mut flags = MemPolicyFlags::empty();
+        for flag in flag_str.into_iter().flat_map(|f| f.split('|')) {
+            match flag {
+                "static" => flags |= MemPolicyFlags::STATIC_NODES,
+                "relative" => flags |= MemPolicyFlags::RELATIVE_NODES,
+                "balancing" => flags |= MemPolicyFlags::NUMA_BALANCING,
+                _ => {}
+            }
+        }
+
+        let mut node_list = Vec::new();
+        if let Some(nodes) = nodes {
+            for range in nodes.split(',').filter(|r| !r.is_empty()) {
+                match range.split_once('-') {
+                    Some((start, end)) => node_list.extend(from_str!(u32, start)..=from_str!(u32, end)),
+                    None => node_list.push(from_str!(u32, range)),
+                }
+            }
+        }
+
+        Ok(MemPolicy {
+            mode: MemPolicyMode::from(mode),
+            flags,
+            nodes: node_list,
+        })
+    }
+}
+
+/// Represents an entry in a `/proc/<pid>/numa_maps` file.
+///
+/// Page counts are given in units of [`kernel_page_size`](NumaMap::kernel_page_size), which for
+/// huge page mappings is the size of a huge page.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct NumaMap {
+    /// The starting address of the mapping
+    pub start: u64,
+    /// The ending address of the mapping
+    ///
+    /// The `numa_maps` file only contains the starting address of each mapping, so this will be
+    /// `None` unless it was filled in from a [`MemoryMaps`] (see [`NumaMaps::fill_ranges()`]).
+    pub end: Option<u64>,
+    /// The memory policy for this mapping
+    pub policy: MemPolicy,
+    /// The file backing this mapping, or one of [`MMapPath::Heap`], [`MMapPath::Stack`] or
+    /// [`MMapPath::Anonymous`]
+    pub pathname: MMapPath,
+    /// Whether this mapping is backed by huge pages
+    pub huge: bool,
+    /// Number of anonymous pages
+    pub anon: Option<u64>,
+    /// Number of dirty pages
+    pub dirty: Option<u64>,
+    /// Number of pages mapped, if different from the `anon` and `dirty` counts
+    pub mapped: Option<u64>,
+    /// The maximum number of processes that map a single page in this mapping, if greater than 1
+    pub mapmax: Option<u64>,
+    /// Number of pages that have an associated entry on a swap device
+    pub swapcache: Option<u64>,
+    /// Number of pages on the active list, if different from the number of pages mapped
+    pub active: Option<u64>,
+    /// Number of pages that are currently being written out to disk
+    pub writeback: Option<u64>,
+    /// Number of pages allocated on each node, keyed by node ID
+    pub nodes: BTreeMap<u32, u64>,
+    /// The size of the pages backing this mapping, in bytes
+    pub kernel_page_size: Option<u64>,
+}
+
+impl NumaMap {
+    fn from_line(line: &str) -> ProcResult<NumaMap> {
+        let (start, rest) = expect!(line.split_once(' '));
+
+        // A few policy names contain a space, so they can't simply be split on whitespace
+        let (policy, rest) = match ["prefer (many)", "weighted interleave"]
+            .iter()
+            .find(|p| rest.starts_with(*p))
+        {
+            Some(p) => rest.split_at(rest[p.len()..].find(' ').map_or(rest.len(), |i| i + p.len())),
+            None => rest.split_once(' ').unwrap_or((rest, "")),
+        };
+
+        let mut map = NumaMap {
+            start: from_str!(u64, start, 16),
+            end: None,
+            policy: MemPolicy::from_str(policy)?,
+            pathname: MMapPath::Anonymous,
+            huge: false,
+            anon: None,
+            dirty: None,
+            mapped: None,
+            mapmax: None,
+            swapcache: None,
+            active: None,
+            writeback: None,
+            nodes: BTreeMap::new(),
+            kernel_page_size: None,
+        };
+
+        for tok in rest.split_whitespace() {
+            let (key, value) = match tok.split_once('=') {
+                Some((k, v)) => (k, v),
+                None => {
+                    match tok {
+                        "heap" => map.pathname = MMapPath::Heap,
+                        "stack" => map.pathname = MMapPath::Stack,
+                        "huge" => map.huge = true,
+                        _ => {}
+                    }
+                    continue;
+                }
+            };
+            match key {
+                "file" => map.pathname = MMapPath::from(value)?,
+                "anon" => map.anon = Some(from_str!(u64, value)),
+                "dirty" => map.dirty = Some(from_str!(u64, value)),
+                "mapped" => map.mapped = Some(from_str!(u64, value)),
+                "mapmax" => map.mapmax = Some(from_str!(u64, value)),
+                "swapcache" => map.swapcache = Some(from_str!(u64, value)),
+                "active" => map.active = Some(from_str!(u64, value)),
+                "writeback" => map.writeback = Some(from_str!(u64, value)),
+                "kernelpagesize_kB" => map.kernel_page_size = Some(from_str!(u64, value) * 1024),
+                k if k.starts_with('N') => {
+                    if let Ok(node) = k[1..].parse() {
+                        map.nodes.insert(node, from_str!(u64, value));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Returns the address range of this mapping, if known
+    pub fn address(&self) -> Option<(u64, u64)> {
+        self.end.map(|end| (self.start, end))
+    }
+
+    /// Returns the total number of pages in this mapping that are allocated on any node
+    pub fn total_pages(&self) -> u64 {
+        self.nodes.values().sum()
+    }
+}
+
+/// Represents all entries in a `/proc/<pid>/numa_maps` file.
+///
+/// (since Linux 2.6.14 and requires CONFIG_NUMA)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct NumaMaps(pub Vec<NumaMap>);
+
+impl crate::FromBufRead for NumaMaps {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut v = Vec::new();
+        for line in r.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            v.push(NumaMap::from_line(&line)?);
+        }
+        Ok(NumaMaps(v))
+    }
+}
+
+impl NumaMaps {
+    /// Return an iterator over [NumaMap].
+    pub fn iter(&self) -> std::slice::Iter<'_, NumaMap> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Fills in the [`end`](NumaMap::end) address of each mapping, by matching its starting address
+    /// against the mappings in `maps`.
+    ///
+    /// Mappings without a match in `maps` (for example because the process changed its mappings
+    /// between reading the two files) are left unchanged.
+    pub fn fill_ranges(&mut self, maps: &MemoryMaps) {
+        let ends: BTreeMap<u64, u64> = maps.iter().map(|m| m.address).collect();
+        for map in self.0.iter_mut() {
+            if let Some(end) = ends.get(&map.start) {
+                map.end = Some(*end);
+            }
+        }
+    }
+
+    /// Returns the total number of pages allocated on each node, summed over all mappings
+    ///
+    /// Note that pages are counted without regard to their size, so a huge page counts the same
+    /// as a regular page.  See [`node_bytes()`](NumaMaps::node_bytes) for a count in bytes.
+    pub fn node_pages(&self) -> BTreeMap<u32, u64> {
+        let mut totals = BTreeMap::new();
+        for map in &self.0 {
+            for (node, pages) in &map.nodes {
+                *totals.entry(*node).or_insert(0) += pages;
+            }
+        }
+        totals
+    }
+
+    /// Returns the total number of bytes allocated on each node, summed over all mappings
+    ///
+    /// `page_size` is used for any mappings that don't report their kernel page size.
+    pub fn node_bytes(&self, page_size: u64) -> BTreeMap<u32, u64> {
+        let mut totals = BTreeMap::new();
+        for map in &self.0 {
+            let size = map.kernel_page_size.unwrap_or(page_size);
+            for (node, pages) in &map.nodes {
+                *totals.entry(*node).or_insert(0) += pages * size;
+            }
+        }
+        totals
+    }
+}
+
+impl<'a> IntoIterator for &'a NumaMaps {
+    type IntoIter = std::slice::Iter<'a, NumaMap>;
+    type Item = &'a NumaMap;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for NumaMaps {
+    type IntoIter = std::vec::IntoIter<NumaMap>;
+    type Item = NumaMap;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_numa_maps() {
+        let data = r#"55d0ae3a8000 default file=/usr/bin/cat mapped=2 active=0 N0=2 kernelpagesize_kB=4
+55d0aee4f000 default heap anon=33 dirty=33 active=0 N0=20 N1=13 kernelpagesize_kB=4
+7f0e2c000000 bind=static:0-1,3 anon=1 dirty=1 N1=1 kernelpagesize_kB=4
+7f0e2e000000 prefer (many)=balancing:1 anon=2 dirty=2 N1=2 kernelpagesize_kB=4
+7f0e30000000 interleave:0-1 file=/dev/hugepages/x huge dirty=1 N0=1 kernelpagesize_kB=2048
+7ffd5c85a000 default stack anon=3 dirty=3 N0=3 kernelpagesize_kB=4
+"#;
+        let mut maps = NumaMaps::from_read(data.as_bytes()).unwrap();
+        assert_eq!(maps.len(), 6);
+
+        let cat = &maps.0[0];
+        assert_eq!(cat.start, 0x55d0ae3a8000);
+        assert_eq!(cat.policy.mode, MemPolicyMode::Default);
+        assert_eq!(cat.pathname, MMapPath::Path("/usr/bin/cat".into()));
+        assert_eq!(cat.mapped, Some(2));
+        assert_eq!(cat.kernel_page_size, Some(4096));
+
+        let heap = &maps.0[1];
+        assert_eq!(heap.pathname, MMapPath::Heap);
+        assert_eq!(heap.total_pages(), 33);
+
+        let bind = &maps.0[2];
+        assert_eq!(bind.policy.mode, MemPolicyMode::Bind);
+        assert_eq!(bind.policy.flags, MemPolicyFlags::STATIC_NODES);
+        // MPOL_F_STATIC_NODES
+        assert_eq!(MemPolicyFlags::from_bits(1 << 15), Some(MemPolicyFlags::STATIC_NODES));
+        assert_eq!(bind.policy.nodes, vec![0, 1, 3]);
+
+        let many = &maps.0[3];
+        assert_eq!(many.policy.mode, MemPolicyMode::PreferredMany);
+        assert_eq!(many.policy.flags, MemPolicyFlags::NUMA_BALANCING);
+        assert_eq!(many.policy.nodes, vec![1]);
+        assert_eq!(many.anon, Some(2));
+
+        let huge = &maps.0[4];
+        assert!(huge.huge);
+        assert_eq!(huge.policy.mode, MemPolicyMode::Interleave);
+
+        assert_eq!(maps.0[5].pathname, MMapPath::Stack);
+
+        let totals = maps.node_pages();
+        assert_eq!(totals[&0], 26);
+        assert_eq!(totals[&1], 16);
+        assert_eq!(maps.node_bytes(4096)[&0], 25 * 4096 + 2048 * 1024);
+
+        let memory_maps =
+            MemoryMaps::from_read("55d0ae3a8000-55d0ae3aa000 r--p 00000000 fd:01 1234 /usr/bin/cat\n".as_bytes())
+                .unwrap();
+        maps.fill_ranges(&memory_maps);
+        assert_eq!(maps.0[0].address(), Some((0x55d0ae3a8000, 0x55d0ae3aa000)));
+        assert_eq!(maps.0[1].address(), None);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{FromBufRead, ProcResult};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Scheduler debugging information about a process or thread, from the `/proc/<pid>/sched` file.
+///
+/// The contents of this file are not a stable kernel interface, so only the most commonly used
+/// fields are parsed here.  All other fields are available (unparsed) in the
+/// [other](Sched::other) map.
+///
+/// All times are in nanoseconds.
+///
+/// The `wait_*`, `sleep_*`, `block_*` and `exec_max` fields are only present if the kernel was
+/// built with `CONFIG_SCHEDSTATS`, and are only updated while schedstats are enabled (see the
+/// `kernel.sched_schedstats` sysctl).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct Sched {
+    /// The command name of the thread
+    pub comm: String,
+    /// The thread ID (in the PID namespace of the procfs mount)
+    pub pid: i32,
+    /// The number of threads in the thread group
+    pub threads: u64,
+    /// Total time spent running on a CPU (`se.sum_exec_runtime`)
+    pub sum_exec_runtime: u64,
+    /// Number of migrations between CPUs (`se.nr_migrations`)
+    pub nr_migrations: u64,
+    /// Total number of context switches
+    pub nr_switches: u64,
+    /// Number of voluntary context switches
+    pub nr_voluntary_switches: u64,
+    /// Number of involuntary context switches
+    pub nr_involuntary_switches: u64,
+    /// Longest time spent waiting on a runqueue
+    pub wait_max: Option<u64>,
+    /// Total time spent waiting on a runqueue
+    pub wait_sum: Option<u64>,
+    /// Number of times the thread waited on a runqueue
+    pub wait_count: Option<u64>,
+    /// Longest time spent in an interruptible sleep
+    pub sleep_max: Option<u64>,
+    /// Longest time spent in an uninterruptible sleep
+    pub block_max: Option<u64>,
+    /// Longest time spent running on a CPU without being descheduled
+    pub exec_max: Option<u64>,
+    /// Total time spent sleeping
+    pub sum_sleep_runtime: Option<u64>,
+    /// Total time spent blocked (since Linux 5.16)
+    pub sum_block_runtime: Option<u64>,
+    /// Scheduling policy (see `sched_setscheduler(2)`)
+    pub policy: u32,
+    /// Kernel priority of the thread
+    ///
+    /// For normal (non-realtime) threads, this is `120 + nice`.
+    pub prio: i32,
+    /// NUMA balancing information
+    ///
+    /// This is only present if the kernel was built with `CONFIG_NUMA_BALANCING`.
+    pub numa: Option<SchedNuma>,
+    /// All other fields, keyed by their name in the file
+    pub other: HashMap<String, String>,
+}
+
+/// NUMA balancing information, from the `/proc/<pid>/sched` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct SchedNuma {
+    /// Number of NUMA scans of the address space (`mm->numa_scan_seq`)
+    pub scan_seq: u64,
+    /// Number of pages migrated to the preferred node
+    pub pages_migrated: u64,
+    /// The preferred NUMA node, or -1 if there is none
+    pub preferred_nid: i32,
+    /// Total number of NUMA hinting faults
+    pub total_faults: u64,
+    /// The node that the thread is currently running on
+    pub current_node: Option<i32>,
+    /// The ID of the NUMA group of the thread (0 if it isn't in a group)
+    pub group_id: Option<u32>,
+    /// Per-node NUMA hinting fault statistics
+    pub faults: Vec<NumaFaults>,
+}
+
+/// NUMA hinting fault statistics for a single node
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct NumaFaults {
+    pub node: u32,
+    /// Faults on private pages, for this thread
+    pub task_private: u64,
+    /// Faults on shared pages, for this thread
+    pub task_shared: u64,
+    /// Faults on private pages, for the NUMA group of this thread
+    pub group_private: u64,
+    /// Faults on shared pages, for the NUMA group of this thread
+    pub group_shared: u64,
+}
+
+/// Parses a time in the `<msecs>.<nsecs>` format, as nanoseconds
+fn parse_ns(s: &str) -> ProcResult<u64> {
+    match s.split_once('.') {
+        Some((ms, ns)) => Ok(from_str!(u64, ms) * 1_000_000 + from_str!(u64, ns)),
+        None => Ok(from_str!(u64, s)),
+    }
+}
+
+fn parse_key_values(s: &str) -> ProcResult<HashMap<&str, i64>> {
+    let mut map = HashMap::new();
+    for kv in s.split(|c: char| c == ',' || c.is_whitespace()) {
+        if let Some((k, v)) = kv.split_once('=') {
+            map.insert(k, from_str!(i64, v));
+        }
+    }
+    Ok(map)
+}
+
+impl FromBufRead for Sched {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut lines = r.lines();
+
+        // the first line looks like "cat (713, #threads: 1)"
+        let header = expect!(lines.next())?;
+        let (comm, rest) = expect!(header.rsplit_once(" ("), "Missing pid in sched header");
+        let (pid, threads) = expect!(
+            expect!(rest.strip_suffix(')')).split_once(", #threads: "),
+            "Missing thread count in sched header"
+        );
+        let (comm, pid, threads) = (comm.to_string(), from_str!(i32, pid), from_str!(u64, threads));
+
+        let mut map = HashMap::new();
+        let mut current_node = None;
+        let mut group_id = None;
+        let mut faults = Vec::new();
+        for line in lines {
+            let line = line?;
+            if let Some((key, value)) = line.split_once(':') {
+                let key = key.trim();
+                // before Linux 5.16, schedstat fields were prefixed with "se.statistics."
+                let key = key.strip_prefix("se.statistics.").unwrap_or(key);
+                map.insert(key.to_string(), value.trim().to_string());
+            } else if line.starts_with("current_node=") {
+                let kv = parse_key_values(&line)?;
+                current_node = kv.get("current_node").map(|v| *v as i32);
+                group_id = kv.get("numa_group_id").map(|v| *v as u32);
+            } else if let Some(rest) = line.strip_prefix("numa_faults ") {
+                let kv = parse_key_values(rest)?;
+                faults.push(NumaFaults {
+                    node: *expect!(kv.get("node")) as u32,
+                    task_private: kv.get("task_private").copied().unwrap_or(0) as u64,
+                    task_shared: kv.get("task_shared").copied().unwrap_or(0) as u64,
+                    group_private: kv.get("group_private").copied().unwrap_or(0) as u64,
+                    group_shared: kv.get("group_shared").copied().unwrap_or(0) as u64,
+                });
+            }
+        }
+
+        let mut take_ns = |key: &str| map.remove(key).map(|v| parse_ns(&v)).transpose();
+        let sum_exec_runtime = expect!(take_ns("se.sum_exec_runtime")?);
+        let wait_max = take_ns("wait_max")?;
+        let wait_sum = take_ns("wait_sum")?;
+        let sleep_max = take_ns("sleep_max")?;
+        let block_max = take_ns("block_max")?;
+        let exec_max = take_ns("exec_max")?;
+        let sum_sleep_runtime = take_ns("sum_sleep_runtime")?;
+        let sum_block_runtime = take_ns("sum_block_runtime")?;
+
+        let numa = match map.remove("mm->numa_scan_seq") {
+            Some(scan_seq) => Some(SchedNuma {
+                scan_seq: from_str!(u64, &scan_seq),
+                pages_migrated: from_str!(u64, &expect!(map.remove("numa_pages_migrated"))),
+                preferred_nid: from_str!(i32, &expect!(map.remove("numa_preferred_nid"))),
+                total_faults: from_str!(u64, &expect!(map.remove("total_numa_faults"))),
+                current_node,
+                group_id,
+                faults,
+            }),
+            None => None,
+        };
+
+        Ok(Sched {
+            comm,
+            pid,
+            threads,
+            sum_exec_runtime,
+            nr_migrations: from_str!(u64, &expect!(map.remove("se.nr_migrations"))),
+            nr_switches: from_str!(u64, &expect!(map.remove("nr_switches"))),
+            nr_voluntary_switches: from_str!(u64, &expect!(map.remove("nr_voluntary_switches"))),
+            nr_involuntary_switches: from_str!(u64, &expect!(map.remove("nr_involuntary_switches"))),
+            wait_max,
+            wait_sum,
+            wait_count: map.remove("wait_count").map(|v| Ok(from_str!(u64, &v))).transpose()?,
+            sleep_max,
+            block_max,
+            exec_max,
+            sum_sleep_runtime,
+            sum_block_runtime,
+            policy: from_str!(u32, &expect!(map.remove("policy"))),
+            prio: from_str!(i32, &expect!(map.remove("prio"))),
+            numa,
+            other: map,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sched() {
+        let data = r#"cat (713, #threads: 1)
+-------------------------------------------------------------------
+se.exec_start                                :       1999783.903723
+se.vruntime                                  :             3.885811
+se.sum_exec_runtime                          :             1.203050
+se.nr_migrations                             :                    2
+sum_sleep_runtime                            :             0.000000
+sum_block_runtime                            :             0.315219
+wait_start                                   :             0.000000
+sleep_start                                  :             0.000000
+block_start                                  :             0.000000
+sleep_max                                    :             0.000000
+block_max                                    :             0.315219
+exec_max                                     :             0.810355
+slice_max                                    :             0.000000
+wait_max                                     :             0.027345
+wait_sum                                     :             0.041152
+wait_count                                   :                    4
+nr_switches                                  :                    5
+nr_voluntary_switches                        :                    1
+nr_involuntary_switches                      :                    4
+se.load.weight                               :              1048576
+se.avg.util_avg                              :                  172
+policy                                       :                    0
+prio                                         :                  120
+clock-delta                                  :                   94
+mm->numa_scan_seq                            :                    0
+numa_pages_migrated                          :                    0
+numa_preferred_nid                           :                   -1
+total_numa_faults                            :                    0
+current_node=0, numa_group_id=0
+numa_faults node=0 task_private=0 task_shared=0 group_private=0 group_shared=0
+numa_faults node=1 task_private=3 task_shared=1 group_private=0 group_shared=0
+"#;
+        let sched = Sched::from_buf_read(data.as_bytes()).unwrap();
+        assert_eq!(sched.comm, "cat");
+        assert_eq!(sched.pid, 713);
+        assert_eq!(sched.threads, 1);
+        assert_eq!(sched.sum_exec_runtime, 1_203_050);
+        assert_eq!(sched.nr_migrations, 2);
+        assert_eq!(sched.nr_switches, 5);
+        assert_eq!(sched.nr_voluntary_switches, 1);
+        assert_eq!(sched.nr_involuntary_switches, 4);
+        assert_eq!(sched.wait_max, Some(27_345));
+        assert_eq!(sched.wait_sum, Some(41_152));
+        assert_eq!(sched.wait_count, Some(4));
+        assert_eq!(sched.block_max, Some(315_219));
+        assert_eq!(sched.exec_max, Some(810_355));
+        assert_eq!(sched.policy, 0);
+        assert_eq!(sched.prio, 120);
+        assert_eq!(sched.other.get("se.load.weight").map(|s| s.as_str()), Some("1048576"));
+        assert_eq!(sched.other.get("se.vruntime").map(|s| s.as_str()), Some("3.885811"));
+
+        let numa = sched.numa.unwrap();
+        assert_eq!(numa.preferred_nid, -1);
+        assert_eq!(numa.current_node, Some(0));
+        assert_eq!(numa.group_id, Some(0));
+        assert_eq!(numa.faults.len(), 2);
+        assert_eq!(numa.faults[1].node, 1);
+        assert_eq!(numa.faults[1].task_private, 3);
+    }
+
+    #[test]
+    fn test_sched_old() {
+        // Linux 4.15, without NUMA balancing
+        let data = r#"kworker/u8:0 (ipc) (4711, #threads: 1)
+---------------------------------------------------------
+se.exec_start                                :      45123456.100000
+se.vruntime                                  :        341239.492221
+se.sum_exec_runtime                          :           123.456789
+se.nr_migrations                             :                   17
+se.statistics.wait_max                       :             1.500000
+se.statistics.wait_sum                       :            10.000000
+se.statistics.wait_count                     :                  300
+nr_switches                                  :                  300
+nr_voluntary_switches                        :                  290
+nr_involuntary_switches                      :                   10
+policy                                       :                    1
+prio                                         :                   98
+"#;
+        let sched = Sched::from_buf_read(data.as_bytes()).unwrap();
+        assert_eq!(sched.comm, "kworker/u8:0 (ipc)");
+        assert_eq!(sched.pid, 4711);
+        assert_eq!(sched.sum_exec_runtime, 123_456_789);
+        assert_eq!(sched.wait_max, Some(1_500_000));
+        assert_eq!(sched.wait_count, Some(300));
+        assert_eq!(sched.sleep_max, None);
+        assert_eq!(sched.policy, 1);
+        assert_eq!(sched.prio, 98);
+        assert!(sched.numa.is_none());
+        assert!(!sched.other.contains_key("se.statistics.wait_max"));
+    }
+}
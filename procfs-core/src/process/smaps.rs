@@ -0,0 +1,386 @@
+use super::{MMapExtension, MMapPath, MemoryMap, MemoryMaps};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The memory statistics of a mapping in `/proc/<pid>/smaps` (or of all mappings, in
+/// `/proc/<pid>/smaps_rollup`)
+///
+/// Created by [MMapExtension::smaps_entry()].  All sizes are in bytes.  Fields that were added in
+/// recent kernels are `None` when the kernel doesn't report them; the other fields are zero if
+/// they're missing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct SmapsEntry {
+    /// Size of the mapping
+    pub size: u64,
+    /// Page size used by the kernel for this mapping
+    pub kernel_page_size: u64,
+    /// Page size used by the MMU for this mapping
+    pub mmu_page_size: u64,
+    /// Resident set size
+    pub rss: u64,
+    /// Proportional set size: the resident memory, with each page divided by the number of
+    /// processes sharing it
+    pub pss: u64,
+    /// Proportional set size of dirty pages
+    ///
+    /// (since Linux 6.0)
+    pub pss_dirty: Option<u64>,
+    /// Proportional set size of anonymous pages
+    ///
+    /// (since Linux 5.9)
+    pub pss_anon: Option<u64>,
+    /// Proportional set size of file-backed pages
+    ///
+    /// (since Linux 5.9)
+    pub pss_file: Option<u64>,
+    /// Proportional set size of shared memory (shmem/tmpfs) pages
+    ///
+    /// (since Linux 5.9)
+    pub pss_shmem: Option<u64>,
+    /// Clean pages that are mapped by other processes too
+    pub shared_clean: u64,
+    /// Dirty pages that are mapped by other processes too
+    pub shared_dirty: u64,
+    /// Clean pages that are only mapped by this process
+    pub private_clean: u64,
+    /// Dirty pages that are only mapped by this process
+    pub private_dirty: u64,
+    /// Memory that has been marked as referenced or accessed
+    pub referenced: u64,
+    /// Memory that doesn't belong to any file
+    pub anonymous: u64,
+    /// Memory that was marked with `MADV_FREE`, and can be reclaimed without writing it to swap
+    ///
+    /// (since Linux 4.12)
+    pub lazy_free: Option<u64>,
+    /// Anonymous memory backed by transparent huge pages
+    pub anon_huge_pages: u64,
+    /// Shared memory backed by transparent huge pages
+    pub shmem_pmd_mapped: u64,
+    /// File-backed memory backed by transparent huge pages
+    ///
+    /// (since Linux 4.8)
+    pub file_pmd_mapped: Option<u64>,
+    /// Shared memory backed by hugetlbfs pages
+    pub shared_hugetlb: u64,
+    /// Private memory backed by hugetlbfs pages
+    pub private_hugetlb: u64,
+    /// Anonymous memory that was swapped out
+    pub swap: u64,
+    /// Proportional share of swapped out memory, with each page divided by the number of
+    /// processes sharing it
+    pub swap_pss: u64,
+    /// Memory that is locked in RAM
+    pub locked: u64,
+    /// Whether the mapping is eligible for transparent huge pages
+    ///
+    /// (since Linux 5.0)
+    pub thp_eligible: Option<bool>,
+}
+
+impl SmapsEntry {
+    /// Unique set size: the memory that is only mapped by this process, and would be freed if
+    /// it exited
+    pub fn uss(&self) -> u64 {
+        self.private_clean + self.private_dirty
+    }
+}
+
+impl MMapExtension {
+    /// Returns the typed memory statistics of this mapping
+    ///
+    /// This is only useful for mappings that were read from `/proc/<pid>/smaps` or
+    /// `/proc/<pid>/smaps_rollup`.  For other mappings, all values are zero.
+    pub fn smaps_entry(&self) -> SmapsEntry {
+        let get = |key: &str| self.map.get(key).copied();
+        let size = |key: &str| get(key).unwrap_or(0);
+
+        SmapsEntry {
+            size: size("Size"),
+            kernel_page_size: size("KernelPageSize"),
+            mmu_page_size: size("MMUPageSize"),
+            rss: size("Rss"),
+            pss: size("Pss"),
+            pss_dirty: get("Pss_Dirty"),
+            pss_anon: get("Pss_Anon"),
+            pss_file: get("Pss_File"),
+            pss_shmem: get("Pss_Shmem"),
+            shared_clean: size("Shared_Clean"),
+            shared_dirty: size("Shared_Dirty"),
+            private_clean: size("Private_Clean"),
+            private_dirty: size("Private_Dirty"),
+            referenced: size("Referenced"),
+            anonymous: size("Anonymous"),
+            lazy_free: get("LazyFree"),
+            anon_huge_pages: size("AnonHugePages"),
+            shmem_pmd_mapped: size("ShmemPmdMapped"),
+            file_pmd_mapped: get("FilePmdMapped"),
+            shared_hugetlb: size("Shared_Hugetlb"),
+            private_hugetlb: size("Private_Hugetlb"),
+            swap: size("Swap"),
+            swap_pss: size("SwapPss"),
+            locked: size("Locked"),
+            thp_eligible: get("THPeligible").map(|v| v != 0),
+        }
+    }
+}
+
+/// The category of a memory mapping, used to group mappings in a [MemoryBreakdown]
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum MemoryCategory {
+    /// The process heap
+    Heap,
+    /// The stacks of the main thread and of other threads
+    Stack,
+    /// Anonymous mappings (including named ones, like `[anon:name]`)
+    Anonymous,
+    /// Memory provided by the kernel: the vDSO, vvar and vsyscall pages
+    Vdso,
+    /// A shared library, identified by a `.so` file name
+    SharedLibrary(PathBuf),
+    /// Any other mapped file, including the executable itself
+    File(PathBuf),
+    /// A System V shared memory segment, with its key
+    SysVShm(i32),
+    /// Some other pseudo-path
+    Other(String),
+}
+
+impl MemoryCategory {
+    /// Returns the category of the given mapping
+    pub fn of(map: &MemoryMap) -> MemoryCategory {
+        match &map.pathname {
+            MMapPath::Heap => MemoryCategory::Heap,
+            MMapPath::Stack | MMapPath::TStack(_) => MemoryCategory::Stack,
+            MMapPath::Anonymous => MemoryCategory::Anonymous,
+            MMapPath::Vdso | MMapPath::Vvar | MMapPath::Vsyscall => MemoryCategory::Vdso,
+            MMapPath::Vsys(key) => MemoryCategory::SysVShm(*key),
+            MMapPath::Other(name) if name.starts_with("anon:") || name.starts_with("anon_shmem:") => {
+                MemoryCategory::Anonymous
+            }
+            MMapPath::Other(name) => MemoryCategory::Other(name.clone()),
+            MMapPath::Rollup => MemoryCategory::Other("rollup".to_string()),
+            MMapPath::Path(path) => {
+                let is_library = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(".so") || name.contains(".so."));
+                if is_library {
+                    MemoryCategory::SharedLibrary(path.clone())
+                } else {
+                    MemoryCategory::File(path.clone())
+                }
+            }
+        }
+    }
+}
+
+/// Memory usage summed over a group of mappings
+///
+/// All sizes are in bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct MemoryUsage {
+    /// The number of mappings
+    pub mappings: usize,
+    /// Virtual size
+    pub size: u64,
+    /// Resident set size
+    pub rss: u64,
+    /// Proportional set size
+    pub pss: u64,
+    /// Unique set size (private clean and dirty pages)
+    pub uss: u64,
+    /// Resident pages that are shared with other processes
+    pub shared: u64,
+    /// Swapped out memory
+    pub swap: u64,
+    /// Proportional share of swapped out memory
+    pub swap_pss: u64,
+    /// Memory locked in RAM
+    pub locked: u64,
+}
+
+impl MemoryUsage {
+    fn add(&mut self, entry: &SmapsEntry) {
+        self.mappings += 1;
+        self.size += entry.size;
+        self.rss += entry.rss;
+        self.pss += entry.pss;
+        self.uss += entry.uss();
+        self.shared += entry.shared_clean + entry.shared_dirty;
+        self.swap += entry.swap;
+        self.swap_pss += entry.swap_pss;
+        self.locked += entry.locked;
+    }
+
+    fn merge(&mut self, other: &MemoryUsage) {
+        self.mappings += other.mappings;
+        self.size += other.size;
+        self.rss += other.rss;
+        self.pss += other.pss;
+        self.uss += other.uss;
+        self.shared += other.shared;
+        self.swap += other.swap;
+        self.swap_pss += other.swap_pss;
+        self.locked += other.locked;
+    }
+}
+
+/// The memory usage of a process, grouped by the kind of mapping
+///
+/// Created by [MemoryMaps::breakdown()].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct MemoryBreakdown {
+    /// The usage of each category
+    pub categories: BTreeMap<MemoryCategory, MemoryUsage>,
+    /// The usage of all mappings combined
+    pub total: MemoryUsage,
+}
+
+impl MemoryBreakdown {
+    /// Returns the usage of all shared libraries combined
+    pub fn shared_libraries(&self) -> MemoryUsage {
+        let mut ret = MemoryUsage::default();
+        for (category, usage) in &self.categories {
+            if let MemoryCategory::SharedLibrary(_) = category {
+                ret.merge(usage);
+            }
+        }
+        ret
+    }
+}
+
+impl MemoryMaps {
+    /// Groups the mappings by [MemoryCategory] and sums their memory usage, like `smem` does
+    ///
+    /// This needs the statistics from `/proc/<pid>/smaps`; for mappings from
+    /// `/proc/<pid>/maps`, only the number of mappings is counted.
+    pub fn breakdown(&self) -> MemoryBreakdown {
+        let mut ret = MemoryBreakdown::default();
+        for map in self {
+            let entry = map.extension.smaps_entry();
+            ret.categories.entry(MemoryCategory::of(map)).or_default().add(&entry);
+            ret.total.add(&entry);
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_breakdown() {
+        let smaps = "\
+55d0c8a00000-55d0c8a21000 r-xp 00000000 fd:01 1234 /usr/bin/app
+Size:                132 kB
+KernelPageSize:        4 kB
+MMUPageSize:           4 kB
+Rss:                 100 kB
+Pss:                  60 kB
+Pss_Dirty:             0 kB
+Pss_Anon:              0 kB
+Pss_File:             60 kB
+Pss_Shmem:             0 kB
+Shared_Clean:         80 kB
+Shared_Dirty:          0 kB
+Private_Clean:        20 kB
+Private_Dirty:         0 kB
+Referenced:          100 kB
+Anonymous:             0 kB
+LazyFree:              0 kB
+AnonHugePages:         0 kB
+ShmemPmdMapped:        0 kB
+FilePmdMapped:         0 kB
+Shared_Hugetlb:        0 kB
+Private_Hugetlb:       0 kB
+Swap:                  0 kB
+SwapPss:               0 kB
+Locked:                0 kB
+THPeligible:    0
+VmFlags: rd ex mr mw me dw
+55d0c9000000-55d0c9100000 rw-p 00000000 00:00 0 [heap]
+Size:               1024 kB
+Rss:                 512 kB
+Pss:                 512 kB
+Private_Dirty:       500 kB
+Private_Clean:        12 kB
+Swap:                 64 kB
+SwapPss:              64 kB
+7f0000000000-7f0000100000 r-xp 00000000 fd:01 5678 /usr/lib/libc.so.6
+Size:               1024 kB
+Rss:                 800 kB
+Pss:                  40 kB
+Shared_Clean:        800 kB
+7f0000200000-7f0000300000 rw-p 00000000 00:00 0 
+Size:               1024 kB
+Rss:                 256 kB
+Pss:                 256 kB
+Private_Dirty:       256 kB
+Locked:              256 kB
+7f0000300000-7f0000301000 rw-p 00000000 00:00 0 [anon:arena]
+Size:                  4 kB
+Rss:                   4 kB
+Pss:                   4 kB
+Private_Dirty:         4 kB
+7ffd00000000-7ffd00021000 rw-p 00000000 00:00 0 [stack]
+Size:                132 kB
+Rss:                  16 kB
+Pss:                  16 kB
+Private_Dirty:        16 kB
+7ffd00100000-7ffd00102000 r-xp 00000000 00:00 0 [vdso]
+Size:                  8 kB
+Rss:                   4 kB
+Pss:                   0 kB
+Shared_Clean:          4 kB
+";
+        let maps = MemoryMaps::from_read(smaps.as_bytes()).unwrap();
+
+        let entry = maps.0[0].extension.smaps_entry();
+        assert_eq!(entry.rss, 100 * 1024);
+        assert_eq!(entry.pss_file, Some(60 * 1024));
+        assert_eq!(entry.uss(), 20 * 1024);
+        assert_eq!(entry.thp_eligible, Some(false));
+        let entry = maps.0[1].extension.smaps_entry();
+        assert_eq!(entry.pss_anon, None);
+        assert_eq!(entry.swap_pss, 64 * 1024);
+
+        let breakdown = maps.breakdown();
+        assert_eq!(breakdown.categories.len(), 6);
+        let heap = &breakdown.categories[&MemoryCategory::Heap];
+        assert_eq!(heap.uss, 512 * 1024);
+        assert_eq!(heap.swap, 64 * 1024);
+        let anon = &breakdown.categories[&MemoryCategory::Anonymous];
+        assert_eq!(anon.mappings, 2);
+        assert_eq!(anon.pss, 260 * 1024);
+        assert_eq!(anon.locked, 256 * 1024);
+        let libc = &breakdown.categories[&MemoryCategory::SharedLibrary(PathBuf::from("/usr/lib/libc.so.6"))];
+        assert_eq!(libc.shared, 800 * 1024);
+        assert_eq!(libc.uss, 0);
+        assert_eq!(breakdown.shared_libraries().pss, 40 * 1024);
+        assert!(breakdown
+            .categories
+            .contains_key(&MemoryCategory::File(PathBuf::from("/usr/bin/app"))));
+        assert_eq!(breakdown.categories[&MemoryCategory::Vdso].rss, 4 * 1024);
+        assert_eq!(breakdown.categories[&MemoryCategory::Stack].uss, 16 * 1024);
+
+        assert_eq!(breakdown.total.mappings, 7);
+        assert_eq!(breakdown.total.pss, (60 + 512 + 40 + 256 + 4 + 16) * 1024);
+        assert_eq!(breakdown.total.uss, (20 + 512 + 256 + 4 + 16) * 1024);
+        assert_eq!(breakdown.total.swap, 64 * 1024);
+    }
+}
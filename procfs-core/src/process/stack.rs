@@ -0,0 +1,160 @@
+use crate::ProcResult;
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// A single frame in a [KernelStack]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct StackFrame {
+    /// The address of this frame
+    ///
+    /// Since Linux 4.19, kernel addresses are hidden and this will always be zero.
+    pub address: u64,
+    /// The name of the function
+    ///
+    /// If the kernel wasn't able to resolve the address to a symbol, this is the raw address
+    /// (and `offset` and `size` will both be zero).
+    pub symbol: String,
+    /// The offset of the return address in the function, in bytes
+    pub offset: u64,
+    /// The size of the function, in bytes
+    pub size: u64,
+    /// The kernel module containing the function, if it's not part of the core kernel
+    pub module: Option<String>,
+}
+
+impl StackFrame {
+    fn from_line(line: &str) -> ProcResult<StackFrame> {
+        // Each line looks like "[<0>] do_wait+0x1c1/0x230", optionally followed by a " [module]"
+        let line = expect!(line.strip_prefix("[<"));
+        let (address, rest) = expect!(line.split_once(">]"));
+        let mut parts = rest.split_whitespace();
+        let func = expect!(parts.next());
+        let module = parts
+            .next()
+            .map(|m| m.trim_start_matches('[').trim_end_matches(']').to_string());
+
+        let (symbol, offset, size) = match func.rsplit_once('+') {
+            Some((symbol, offsets)) => {
+                let (offset, size) = expect!(offsets.split_once('/'));
+                (
+                    symbol.to_string(),
+                    from_str!(u64, offset.trim_start_matches("0x"), 16),
+                    from_str!(u64, size.trim_start_matches("0x"), 16),
+                )
+            }
+            None => (func.to_string(), 0, 0),
+        };
+
+        Ok(StackFrame {
+            address: from_str!(u64, address, 16),
+            symbol,
+            offset,
+            size,
+            module,
+        })
+    }
+}
+
+/// The kernel stack of a process or task, from the `/proc/<pid>/stack` or
+/// `/proc/<pid>/task/<tid>/stack` file.
+///
+/// The first frame is the innermost function.
+///
+/// Reading this file requires the `CAP_SYS_ADMIN` capability, and is only possible if the kernel
+/// was built with `CONFIG_STACKTRACE`.
+///
+/// (since Linux 2.6.29)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct KernelStack(pub Vec<StackFrame>);
+
+impl crate::FromBufRead for KernelStack {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut frames = Vec::new();
+        for line in r.lines() {
+            let line = line?;
+            let line = line.trim();
+            // Before Linux 4.9, the stack was terminated by a "[<ffffffffffffffff>] 0xffffffffffffffff" line
+            if line.is_empty() || line.ends_with("] 0xffffffffffffffff") {
+                continue;
+            }
+            frames.push(StackFrame::from_line(line)?);
+        }
+        Ok(KernelStack(frames))
+    }
+}
+
+impl KernelStack {
+    /// Return an iterator over [StackFrame].
+    pub fn iter(&self) -> std::slice::Iter<'_, StackFrame> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a KernelStack {
+    type IntoIter = std::slice::Iter<'a, StackFrame>;
+    type Item = &'a StackFrame;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for KernelStack {
+    type IntoIter = std::vec::IntoIter<StackFrame>;
+    type Item = StackFrame;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_kernel_stack() {
+        let data = r#"[<0>] do_wait+0x1c1/0x230
+[<0>] kernel_wait4+0xa6/0x140
+[<0>] nfs_wait_bit_killable+0x1e/0x90 [nfs]
+[<0>] entry_SYSCALL_64_after_hwframe+0x76/0x7e
+"#;
+        let stack = KernelStack::from_read(data.as_bytes()).unwrap();
+        assert_eq!(stack.len(), 4);
+        assert_eq!(
+            stack.0[0],
+            StackFrame {
+                address: 0,
+                symbol: "do_wait".to_string(),
+                offset: 0x1c1,
+                size: 0x230,
+                module: None,
+            }
+        );
+        assert_eq!(stack.0[2].module.as_deref(), Some("nfs"));
+        assert_eq!(stack.0[3].symbol, "entry_SYSCALL_64_after_hwframe");
+
+        let data = r#"[<ffffffff8108e8f5>] futex_wait_queue_me+0xc5/0x120
+[<ffffffff8108f6d9>] futex_wait+0x179/0x280
+[<ffffffffffffffff>] 0xffffffffffffffff
+"#;
+        let stack = KernelStack::from_read(data.as_bytes()).unwrap();
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.0[0].address, 0xffffffff8108e8f5);
+        assert_eq!(stack.0[1].symbol, "futex_wait");
+    }
+}
@@ -432,4 +432,29 @@ impl Stat {
     pub fn rss_bytes(&self) -> impl crate::WithSystemInfo<Output = u64> {
         move |si: &crate::SystemInfo| self.rss * si.page_size()
     }
+
+    /// Returns a key that identifies this process, even after its PID has been reused
+    pub fn identity(&self) -> ProcessIdentity {
+        ProcessIdentity {
+            pid: self.pid,
+            starttime: self.starttime,
+        }
+    }
+}
+
+/// A key that uniquely identifies a process over the lifetime of the system
+///
+/// PIDs are reused once a process exits, so a PID alone can't be used to tell whether a
+/// process is the one that was seen earlier.  Since two processes with the same PID can't be
+/// started at the same clock tick, the combination of the PID and the
+/// [`starttime`](Stat::starttime) does not have this problem.
+///
+/// This is created with [Stat::identity()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct ProcessIdentity {
+    /// The process ID
+    pub pid: i32,
+    /// The time the process started after system boot, in clock ticks
+    pub starttime: u64,
 }
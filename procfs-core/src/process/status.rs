@@ -1,4 +1,5 @@
-use crate::{FromStrRadix, ProcResult};
+use super::Capabilities;
+use crate::{CpuSet, FromStrRadix, ProcResult};
 use std::collections::HashMap;
 use std::io::BufRead;
 
@@ -337,4 +338,61 @@ impl Status {
         }
         Ok(ret)
     }
+
+    /// Returns the inheritable capability set ([capinh](Status::capinh))
+    pub fn cap_inheritable(&self) -> Capabilities {
+        Capabilities::from_bits_retain(self.capinh)
+    }
+
+    /// Returns the permitted capability set ([capprm](Status::capprm))
+    pub fn cap_permitted(&self) -> Capabilities {
+        Capabilities::from_bits_retain(self.capprm)
+    }
+
+    /// Returns the effective capability set ([capeff](Status::capeff))
+    pub fn cap_effective(&self) -> Capabilities {
+        Capabilities::from_bits_retain(self.capeff)
+    }
+
+    /// Returns the capability bounding set ([capbnd](Status::capbnd))
+    ///
+    /// (since Linux 2.6.26)
+    pub fn cap_bounding(&self) -> Option<Capabilities> {
+        self.capbnd.map(Capabilities::from_bits_retain)
+    }
+
+    /// Returns the ambient capability set ([capamb](Status::capamb))
+    ///
+    /// (since Linux 4.3)
+    pub fn cap_ambient(&self) -> Option<Capabilities> {
+        self.capamb.map(Capabilities::from_bits_retain)
+    }
+
+    /// Returns the set of CPUs on which this process may run
+    ///
+    /// This is taken from [cpus_allowed_list](Status::cpus_allowed_list) if present, and
+    /// otherwise from [cpus_allowed](Status::cpus_allowed).
+    ///
+    /// (since Linux 2.6.24)
+    pub fn cpu_affinity(&self) -> Option<CpuSet> {
+        Status::allowed_set(&self.cpus_allowed_list, &self.cpus_allowed)
+    }
+
+    /// Returns the set of memory nodes allowed to this process
+    ///
+    /// This is taken from [mems_allowed_list](Status::mems_allowed_list) if present, and
+    /// otherwise from [mems_allowed](Status::mems_allowed).
+    ///
+    /// (since Linux 2.6.24)
+    pub fn mem_affinity(&self) -> Option<CpuSet> {
+        Status::allowed_set(&self.mems_allowed_list, &self.mems_allowed)
+    }
+
+    fn allowed_set(list: &Option<Vec<(u32, u32)>>, mask: &Option<Vec<u32>>) -> Option<CpuSet> {
+        match (list, mask) {
+            (Some(list), _) => Some(list.iter().flat_map(|&(start, end)| start..=end).collect()),
+            (None, Some(mask)) => Some(CpuSet::from_mask_words(mask)),
+            (None, None) => None,
+        }
+    }
 }
@@ -0,0 +1,239 @@
+use crate::ProcResult;
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// How a process is notified when a timer expires
+///
+/// See `sigevent(7)` for details.
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum TimerNotify {
+    /// Notify the process by sending a signal (`SIGEV_SIGNAL`)
+    Signal,
+    /// Don't notify the process (`SIGEV_NONE`)
+    None,
+    /// Notify the process by invoking a function in a new thread (`SIGEV_THREAD`)
+    Thread,
+    /// Some other unknown notification method
+    Other(String),
+}
+
+impl From<&str> for TimerNotify {
+    fn from(s: &str) -> TimerNotify {
+        match s {
+            "signal" => TimerNotify::Signal,
+            "none" => TimerNotify::None,
+            "thread" => TimerNotify::Thread,
+            x => TimerNotify::Other(x.to_string()),
+        }
+    }
+}
+
+/// The target of a timer notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub enum TimerNotifyTarget {
+    /// The signal is sent to the process
+    Pid(i32),
+    /// The signal is sent to a specific thread (`SIGEV_THREAD_ID`)
+    Tid(i32),
+}
+
+/// The clock that a timer is measured against
+///
+/// See `clock_gettime(2)` for details.
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ClockId {
+    /// `CLOCK_REALTIME`
+    Realtime,
+    /// `CLOCK_MONOTONIC`
+    Monotonic,
+    /// `CLOCK_PROCESS_CPUTIME_ID`
+    ProcessCputime,
+    /// `CLOCK_THREAD_CPUTIME_ID`
+    ThreadCputime,
+    /// `CLOCK_MONOTONIC_RAW`
+    MonotonicRaw,
+    /// `CLOCK_REALTIME_COARSE`
+    RealtimeCoarse,
+    /// `CLOCK_MONOTONIC_COARSE`
+    MonotonicCoarse,
+    /// `CLOCK_BOOTTIME`
+    Boottime,
+    /// `CLOCK_REALTIME_ALARM`
+    RealtimeAlarm,
+    /// `CLOCK_BOOTTIME_ALARM`
+    BoottimeAlarm,
+    /// `CLOCK_TAI`
+    Tai,
+    /// Some other clock, such as a dynamic CPU-time clock of a specific process (as returned by
+    /// `clock_getcpuclockid(3)`)
+    Other(i32),
+}
+
+impl From<i32> for ClockId {
+    fn from(id: i32) -> ClockId {
+        match id {
+            0 => ClockId::Realtime,
+            1 => ClockId::Monotonic,
+            2 => ClockId::ProcessCputime,
+            3 => ClockId::ThreadCputime,
+            4 => ClockId::MonotonicRaw,
+            5 => ClockId::RealtimeCoarse,
+            6 => ClockId::MonotonicCoarse,
+            7 => ClockId::Boottime,
+            8 => ClockId::RealtimeAlarm,
+            9 => ClockId::BoottimeAlarm,
+            11 => ClockId::Tai,
+            x => ClockId::Other(x),
+        }
+    }
+}
+
+/// A POSIX timer, as created by `timer_create(2)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct Timer {
+    /// The ID of the timer, as returned by `timer_create(2)`
+    pub id: i32,
+    /// The signal number used to deliver notifications
+    pub signal: i32,
+    /// The `sigev_value` that accompanies the notification signal
+    pub sigev_value: u64,
+    /// How notifications are delivered
+    pub notify: TimerNotify,
+    /// The process or thread that notifications are delivered to
+    pub notify_target: TimerNotifyTarget,
+    /// The clock that the timer is measured against
+    pub clock: ClockId,
+}
+
+/// The POSIX timers of a process, from the `/proc/<pid>/timers` file.
+///
+/// (since Linux 3.10 and requires CONFIG_CHECKPOINT_RESTORE)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Timers(pub Vec<Timer>);
+
+impl crate::FromBufRead for Timers {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut timers = Vec::new();
+        let mut lines = r.lines();
+
+        while let Some(line) = lines.next() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let id = expect!(line.strip_prefix("ID:"));
+
+            let line = expect!(lines.next())?;
+            let signal = expect!(line.strip_prefix("signal:"));
+            let (signal, sigev_value) = expect!(signal.trim().split_once('/'));
+
+            let line = expect!(lines.next())?;
+            let notify = expect!(line.strip_prefix("notify:"));
+            let (notify, target) = expect!(notify.trim().split_once('/'));
+            let (kind, target_id) = expect!(target.split_once('.'));
+            let target_id = from_str!(i32, target_id);
+            let notify_target = match kind {
+                "tid" => TimerNotifyTarget::Tid(target_id),
+                _ => TimerNotifyTarget::Pid(target_id),
+            };
+
+            let line = expect!(lines.next())?;
+            let clock = expect!(line.strip_prefix("ClockID:"));
+
+            timers.push(Timer {
+                id: from_str!(i32, id.trim()),
+                signal: from_str!(i32, signal),
+                sigev_value: from_str!(u64, sigev_value.trim_start_matches("0x"), 16),
+                notify: TimerNotify::from(notify),
+                notify_target,
+                clock: ClockId::from(from_str!(i32, clock.trim())),
+            });
+        }
+
+        Ok(Timers(timers))
+    }
+}
+
+impl Timers {
+    /// Return an iterator over [Timer].
+    pub fn iter(&self) -> std::slice::Iter<'_, Timer> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Timers {
+    type IntoIter = std::slice::Iter<'a, Timer>;
+    type Item = &'a Timer;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Timers {
+    type IntoIter = std::vec::IntoIter<Timer>;
+    type Item = Timer;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromRead;
+
+    #[test]
+    fn test_timers() {
+        let data = r#"ID: 1
+signal: 60/00007fff86e452a8
+notify: signal/pid.2634
+ClockID: 0
+ID: 0
+signal: 14/0000000000000000
+notify: thread/tid.2635
+ClockID: -6
+"#;
+        let timers = Timers::from_read(data.as_bytes()).unwrap();
+        assert_eq!(timers.len(), 2);
+        assert_eq!(
+            timers.0[0],
+            Timer {
+                id: 1,
+                signal: 60,
+                sigev_value: 0x7fff86e452a8,
+                notify: TimerNotify::Signal,
+                notify_target: TimerNotifyTarget::Pid(2634),
+                clock: ClockId::Realtime,
+            }
+        );
+        assert_eq!(timers.0[1].notify, TimerNotify::Thread);
+        assert_eq!(timers.0[1].notify_target, TimerNotifyTarget::Tid(2635));
+        assert_eq!(timers.0[1].clock, ClockId::Other(-6));
+
+        assert!(Timers::from_read("".as_bytes()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+//! System-wide scheduler statistics, from the `/proc/schedstat` file
+//!
+//! This file is only present if the kernel was built with `CONFIG_SCHEDSTATS`, and most counters
+//! are only updated while schedstats are enabled (see the `kernel.sched_schedstats` sysctl).
+//!
+//! See also: <https://docs.kernel.org/scheduler/sched-stats.html>
+
+use crate::{CpuSet, FromBufRead, ProcResult};
+use std::io::BufRead;
+
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// System-wide scheduler statistics, from the `/proc/schedstat` file
+///
+/// Versions 15, 16 and 17 of the file format are supported.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct KernelSchedstat {
+    /// The version of the file format
+    pub version: u32,
+    /// The time at which the statistics were collected, in jiffies
+    pub timestamp: u64,
+    /// Per-CPU statistics
+    pub cpus: Vec<CpuSchedstat>,
+}
+
+/// Scheduler statistics for a single CPU
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct CpuSchedstat {
+    /// The CPU number
+    pub cpu: u32,
+    /// Number of times `sched_yield()` was called
+    pub yld_count: u64,
+    /// Number of times `schedule()` was called
+    pub sched_count: u64,
+    /// Number of times `schedule()` left the CPU idle
+    pub sched_goidle: u64,
+    /// Number of times `try_to_wake_up()` was called
+    pub ttwu_count: u64,
+    /// Number of times `try_to_wake_up()` was called to wake up a task on the local CPU
+    pub ttwu_local: u64,
+    /// Total time spent running by tasks on this CPU, in nanoseconds
+    pub rq_cpu_time: u64,
+    /// Total time spent waiting to run by tasks on this CPU, in nanoseconds
+    pub run_delay: u64,
+    /// Number of timeslices run on this CPU
+    pub pcount: u64,
+    /// Per-domain load balancing statistics
+    ///
+    /// This is empty if the kernel was built without `CONFIG_SMP`.
+    pub domains: Vec<DomainSchedstat>,
+}
+
+/// Load balancing statistics for a single scheduling domain of a CPU
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct DomainSchedstat {
+    /// The domain number (0 is the lowest level)
+    pub domain: u32,
+    /// The name of the domain, like `SMT`, `MC` or `NUMA` (since version 17)
+    pub name: Option<String>,
+    /// The CPUs spanned by this domain
+    pub cpus: CpuSet,
+    /// Load balancing statistics when the CPU was idle
+    pub idle: LoadBalanceStats,
+    /// Load balancing statistics when the CPU was busy
+    pub busy: LoadBalanceStats,
+    /// Load balancing statistics when the CPU was just becoming idle
+    pub newly_idle: LoadBalanceStats,
+    /// Number of times active load balancing was attempted
+    pub alb_count: u64,
+    /// Number of times active load balancing failed to move a task
+    pub alb_failed: u64,
+    /// Number of times active load balancing moved a task
+    pub alb_pushed: u64,
+    /// Unused
+    pub sbe_count: u64,
+    /// Unused
+    pub sbe_balanced: u64,
+    /// Unused
+    pub sbe_pushed: u64,
+    /// Unused
+    pub sbf_count: u64,
+    /// Unused
+    pub sbf_balanced: u64,
+    /// Unused
+    pub sbf_pushed: u64,
+    /// Number of wakeups where the waking CPU was in this domain but not the task's previous CPU
+    pub ttwu_wake_remote: u64,
+    /// Number of wakeups that moved a task to the waking CPU because of cache affinity
+    pub ttwu_move_affine: u64,
+    /// Number of wakeups that moved a task to the waking CPU for passive balancing
+    pub ttwu_move_balance: u64,
+}
+
+/// Load balancing statistics for one idle state of a scheduling domain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct LoadBalanceStats {
+    /// Number of times load balancing was attempted
+    pub count: u64,
+    /// Number of times load balancing found the domain already balanced
+    pub balanced: u64,
+    /// Number of times load balancing failed to move any task
+    pub failed: u64,
+    /// Sum of the imbalances discovered (before version 17)
+    pub imbalance: Option<u64>,
+    /// Sum of the load imbalances discovered (since version 17)
+    pub imbalance_load: Option<u64>,
+    /// Sum of the utilization imbalances discovered (since version 17)
+    pub imbalance_util: Option<u64>,
+    /// Sum of the task count imbalances discovered (since version 17)
+    pub imbalance_task: Option<u64>,
+    /// Sum of the misfit task imbalances discovered (since version 17)
+    pub imbalance_misfit: Option<u64>,
+    /// Number of tasks pulled
+    pub gained: u64,
+    /// Number of tasks pulled that were cache-hot
+    pub hot_gained: u64,
+    /// Number of times no busier queue was found
+    pub nobusyq: u64,
+    /// Number of times no busier group was found
+    pub nobusyg: u64,
+}
+
+impl LoadBalanceStats {
+    fn from_fields(version: u32, f: &[u64]) -> LoadBalanceStats {
+        if version >= 17 {
+            LoadBalanceStats {
+                count: f[0],
+                balanced: f[1],
+                failed: f[2],
+                imbalance: None,
+                imbalance_load: Some(f[3]),
+                imbalance_util: Some(f[4]),
+                imbalance_task: Some(f[5]),
+                imbalance_misfit: Some(f[6]),
+                gained: f[7],
+                hot_gained: f[8],
+                nobusyq: f[9],
+                nobusyg: f[10],
+            }
+        } else {
+            LoadBalanceStats {
+                count: f[0],
+                balanced: f[1],
+                failed: f[2],
+                imbalance: Some(f[3]),
+                imbalance_load: None,
+                imbalance_util: None,
+                imbalance_task: None,
+                imbalance_misfit: None,
+                gained: f[4],
+                hot_gained: f[5],
+                nobusyq: f[6],
+                nobusyg: f[7],
+            }
+        }
+    }
+}
+
+impl DomainSchedstat {
+    fn from_line(version: u32, line: &str) -> ProcResult<DomainSchedstat> {
+        let mut s = line.split_whitespace();
+        let domain = from_str!(u32, expect!(expect!(s.next()).strip_prefix("domain")));
+        let name = if version >= 17 {
+            Some(expect!(s.next()).to_string())
+        } else {
+            None
+        };
+        let cpus = CpuSet::from_mask(expect!(s.next()))?;
+        let mut fields = Vec::new();
+        for f in s {
+            fields.push(from_str!(u64, f));
+        }
+
+        let lb_fields = if version >= 17 { 11 } else { 8 };
+        if fields.len() < lb_fields * 3 + 12 {
+            return Err(build_internal_error!(format!(
+                "Not enough fields in schedstat domain line: {:?}",
+                line
+            )));
+        }
+        let lb = |idx: usize| LoadBalanceStats::from_fields(version, &fields[idx * lb_fields..]);
+        // Version 16 swapped the order of the "idle" and "busy" statistics
+        let (idle, busy) = if version >= 16 { (lb(1), lb(0)) } else { (lb(0), lb(1)) };
+        let newly_idle = lb(2);
+        let rest = &fields[lb_fields * 3..];
+
+        Ok(DomainSchedstat {
+            domain,
+            name,
+            cpus,
+            idle,
+            busy,
+            newly_idle,
+            alb_count: rest[0],
+            alb_failed: rest[1],
+            alb_pushed: rest[2],
+            sbe_count: rest[3],
+            sbe_balanced: rest[4],
+            sbe_pushed: rest[5],
+            sbf_count: rest[6],
+            sbf_balanced: rest[7],
+            sbf_pushed: rest[8],
+            ttwu_wake_remote: rest[9],
+            ttwu_move_affine: rest[10],
+            ttwu_move_balance: rest[11],
+        })
+    }
+}
+
+impl CpuSchedstat {
+    fn from_line(line: &str) -> ProcResult<CpuSchedstat> {
+        let mut s = line.split_whitespace();
+        let cpu = from_str!(u32, expect!(expect!(s.next()).strip_prefix("cpu")));
+        let mut next = || -> ProcResult<u64> { Ok(from_str!(u64, expect!(s.next()))) };
+        let yld_count = next()?;
+        // legacy array expiration count, always zero
+        let _ = next()?;
+        Ok(CpuSchedstat {
+            cpu,
+            yld_count,
+            sched_count: next()?,
+            sched_goidle: next()?,
+            ttwu_count: next()?,
+            ttwu_local: next()?,
+            rq_cpu_time: next()?,
+            run_delay: next()?,
+            pcount: next()?,
+            domains: Vec::new(),
+        })
+    }
+}
+
+impl FromBufRead for KernelSchedstat {
+    fn from_buf_read<R: BufRead>(r: R) -> ProcResult<Self> {
+        let mut version = None;
+        let mut timestamp = None;
+        let mut cpus: Vec<CpuSchedstat> = Vec::new();
+
+        for line in r.lines() {
+            let line = line?;
+            if let Some(v) = line.strip_prefix("version ") {
+                version = Some(from_str!(u32, v.trim()));
+            } else if let Some(t) = line.strip_prefix("timestamp ") {
+                timestamp = Some(from_str!(u64, t.trim()));
+            } else if line.starts_with("cpu") {
+                cpus.push(CpuSchedstat::from_line(&line)?);
+            } else if line.starts_with("domain") {
+                let version = expect!(version, "Missing schedstat version");
+                let cpu = expect!(cpus.last_mut(), "Found schedstat domain before any cpu");
+                cpu.domains.push(DomainSchedstat::from_line(version, &line)?);
+            }
+        }
+
+        Ok(KernelSchedstat {
+            version: expect!(version, "Missing schedstat version"),
+            timestamp: expect!(timestamp, "Missing schedstat timestamp"),
+            cpus,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedstat_v15() {
+        let data = r#"version 15
+timestamp 4295623116
+cpu0 11 0 1779738 693406 976234 429578 248131578343 46227373524 1084713
+domain0 00000003 5127 5069 12 58 50 0 0 0 0 0 0 0 0 0 0 0 1431 1409 15 22 7 0 0 0 16 0 16 0 0 0 0 0 0 12049 0 0
+domain1 0000000f 4329 4282 15 47 32 0 0 0 0 0 0 0 0 0 0 0 1275 1243 24 33 8 0 0 0 11 0 11 0 0 0 0 0 0 3591 0 0
+cpu1 9 0 1514433 614451 863612 368941 230493516960 38871542286 893079
+domain0 00000003 5062 5018 9 45 36 0 0 0 0 0 0 0 0 0 0 0 1388 1373 11 14 4 0 0 0 12 0 12 0 0 0 0 0 0 11337 2 0
+domain1 0000000f 4314 4274 16 42 26 0 0 0 0 0 0 0 0 0 0 0 1205 1182 19 26 4 0 0 0 9 0 9 0 0 0 0 0 0 3104 0 0
+"#;
+        let stat = KernelSchedstat::from_buf_read(data.as_bytes()).unwrap();
+        assert_eq!(stat.version, 15);
+        assert_eq!(stat.timestamp, 4295623116);
+        assert_eq!(stat.cpus.len(), 2);
+
+        let cpu = &stat.cpus[0];
+        assert_eq!(cpu.cpu, 0);
+        assert_eq!(cpu.yld_count, 11);
+        assert_eq!(cpu.sched_count, 1779738);
+        assert_eq!(cpu.sched_goidle, 693406);
+        assert_eq!(cpu.ttwu_count, 976234);
+        assert_eq!(cpu.ttwu_local, 429578);
+        assert_eq!(cpu.rq_cpu_time, 248131578343);
+        assert_eq!(cpu.run_delay, 46227373524);
+        assert_eq!(cpu.pcount, 1084713);
+        assert_eq!(cpu.domains.len(), 2);
+
+        let domain = &cpu.domains[1];
+        assert_eq!(domain.domain, 1);
+        assert_eq!(domain.name, None);
+        assert_eq!(domain.cpus.to_string(), "0-3");
+        assert_eq!(domain.idle.count, 4329);
+        assert_eq!(domain.idle.imbalance, Some(47));
+        assert_eq!(domain.idle.gained, 32);
+        assert_eq!(domain.newly_idle.count, 1275);
+        assert_eq!(domain.busy.count, 0);
+        assert_eq!(domain.alb_count, 11);
+        assert_eq!(domain.alb_pushed, 11);
+        assert_eq!(domain.ttwu_wake_remote, 3591);
+        assert_eq!(stat.cpus[1].domains[0].ttwu_move_affine, 2);
+    }
+
+    #[test]
+    fn test_schedstat_v17() {
+        let data = r#"version 17
+timestamp 4298007154
+cpu0 0 0 21876 10391 11006 6078 3003393837 1053307045 11470
+domain0 SMT 00000003 10 0 1 2 3 4 5 6 0 1 0 120 110 4 1 2 3 4 5 0 0 0 20 18 0 0 0 0 0 0 0 0 0 1 0 1 0 0 0 0 0 0 200 1 0
+domain1 PKG 000000ff 30 29 1 0 0 0 0 0 0 0 0 90 85 1 0 0 0 0 3 0 0 0 15 15 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 80 0 0
+"#;
+        let stat = KernelSchedstat::from_buf_read(data.as_bytes()).unwrap();
+        assert_eq!(stat.version, 17);
+        let domain = &stat.cpus[0].domains[0];
+        assert_eq!(domain.name.as_deref(), Some("SMT"));
+        assert_eq!(domain.cpus.to_string(), "0-1");
+        // version 16 and later list the busy statistics first
+        assert_eq!(domain.busy.count, 10);
+        assert_eq!(domain.busy.imbalance, None);
+        assert_eq!(domain.busy.imbalance_load, Some(2));
+        assert_eq!(domain.busy.imbalance_misfit, Some(5));
+        assert_eq!(domain.busy.gained, 6);
+        assert_eq!(domain.idle.count, 120);
+        assert_eq!(domain.idle.nobusyg, 0);
+        assert_eq!(domain.newly_idle.count, 20);
+        assert_eq!(domain.alb_count, 1);
+        assert_eq!(domain.ttwu_wake_remote, 200);
+        assert_eq!(stat.cpus[0].domains[1].cpus.len(), 8);
+    }
+}
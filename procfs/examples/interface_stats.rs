@@ -1,19 +1,22 @@
 //! For each interface, display the number of bytes sent and received, along with a data rate
 
+use procfs::net::InterfaceDeviceStatus;
+use procfs::Current;
+
 fn main() {
     let delay = std::time::Duration::from_secs(2);
 
-    let mut prev_stats = procfs::net::dev_status().unwrap();
+    let mut prev_stats = InterfaceDeviceStatus::current().unwrap();
     let mut prev_now = std::time::Instant::now();
     loop {
         std::thread::sleep(delay);
         let now = std::time::Instant::now();
-        let dev_stats = procfs::net::dev_status().unwrap();
+        let dev_stats = InterfaceDeviceStatus::current().unwrap();
 
-        // calculate diffs from previous
-        let dt = (now - prev_now).as_millis() as f32 / 1000.0;
+        // calculate rates since the previous reading
+        let rates = dev_stats.rates_since(&prev_stats, now - prev_now);
 
-        let mut stats: Vec<_> = dev_stats.values().collect();
+        let mut stats: Vec<_> = dev_stats.0.values().collect();
         stats.sort_by_key(|s| &s.name);
         println!();
         println!(
@@ -25,13 +28,16 @@ fn main() {
             "================", "====================", "===================="
         );
         for stat in stats {
+            let (recv, sent) = rates
+                .get(&stat.name)
+                .map_or((0.0, 0.0), |r| (r.recv_bytes, r.sent_bytes));
             println!(
                 "{:>16}: {:<20}  {:>6.1} kbps  {:<20}  {:>6.1} kbps ",
                 stat.name,
                 stat.recv_bytes,
-                (stat.recv_bytes - prev_stats.get(&stat.name).unwrap().recv_bytes) as f32 / dt / 1000.0,
+                recv / 1000.0,
                 stat.sent_bytes,
-                (stat.sent_bytes - prev_stats.get(&stat.name).unwrap().sent_bytes) as f32 / dt / 1000.0
+                sent / 1000.0
             );
         }
 
@@ -1,36 +1,20 @@
-use procfs::process::{all_processes, Stat};
-
-struct ProcessEntry {
-    stat: Stat,
-    cmdline: Option<Vec<String>>,
-}
+use procfs::process::{all_processes, ProcessNode, ProcessTree, SnapshotFields};
 
 /// Print all processes as a tree.
 /// The tree reflects the hierarchical relationship between parent and child processes.
 fn main() {
-    // Get all processes
-    let processes: Vec<ProcessEntry> = match all_processes() {
+    let snapshots = match all_processes() {
         Err(err) => {
             println!("Failed to read all processes: {}", err);
             return;
         }
-        Ok(processes) => processes,
-    }
-    .filter_map(|v| {
-        v.and_then(|p| {
-            let stat = p.stat()?;
-            let cmdline = p.cmdline().ok();
-            Ok(ProcessEntry { stat, cmdline })
-        })
-        .ok()
-    })
-    .collect();
+        Ok(processes) => processes.snapshots(SnapshotFields::STAT | SnapshotFields::CMDLINE),
+    };
+    // Processes that exit while the snapshots are taken are left out.
+    let tree = ProcessTree::from_snapshots(snapshots.filter_map(|s| s.ok()));
     // Iterate through all processes and start with top-level processes.
-    // Those can be identified by checking if their parent PID is zero.
-    for process in &processes {
-        if process.stat.ppid == 0 {
-            print_process(process, &processes, 0);
-        }
+    for process in tree.roots() {
+        print_process(process, &tree, 0);
     }
 }
 
@@ -39,10 +23,11 @@ fn main() {
 /// It's a depth-first tree exploration.
 ///
 /// depth: The hierarchical depth of the process
-fn print_process(process: &ProcessEntry, all_processes: &Vec<ProcessEntry>, depth: usize) {
+fn print_process(process: &ProcessNode, tree: &ProcessTree, depth: usize) {
     let cmdline = match &process.cmdline {
         Some(cmdline) => cmdline.join(" "),
-        None => "zombie process".into(),
+        // the cmdline couldn't be read, for example because of missing permissions
+        None => format!("[{}]", process.stat.comm),
     };
 
     // Some processes seem to have an empty cmdline.
@@ -52,23 +37,13 @@ fn print_process(process: &ProcessEntry, all_processes: &Vec<ProcessEntry>, dept
 
     // 10 characters width for the pid
     let pid_length = 8;
-    let mut pid = process.stat.pid.to_string();
+    let mut pid = process.pid().to_string();
     pid.push_str(&" ".repeat(pid_length - pid.len()));
 
     let padding = " ".repeat(4 * depth);
     println!("{}{}{}", pid, padding, cmdline);
 
-    let children = get_children(process.stat.pid, all_processes);
-    for child in &children {
-        print_process(child, all_processes, depth + 1);
+    for child in tree.children(process.pid()) {
+        print_process(child, tree, depth + 1);
     }
 }
-
-/// Get all children of a specific process, by iterating through all processes and
-/// checking their parent pid.
-fn get_children(pid: i32, all_processes: &[ProcessEntry]) -> Vec<&ProcessEntry> {
-    all_processes
-        .iter()
-        .filter(|process| process.stat.ppid == pid)
-        .collect()
-}
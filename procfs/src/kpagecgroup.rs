@@ -0,0 +1,215 @@
+use std::{
+    collections::HashMap,
+    io::{BufReader, Read, Seek, SeekFrom},
+    mem::size_of,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    process::{MountInfo, Pfn, Process},
+    FileWrapper, ProcError,
+};
+
+use super::ProcResult;
+
+/// Parse the memory cgroup of physical pages accessing `/proc/kpagecgroup`
+///
+/// Each page is identified by the inode number of the directory of the memory cgroup that it is
+/// charged to.  Use [CgroupResolver] to turn the inode numbers into cgroup paths.
+///
+/// Require root or CAP_SYS_ADMIN, and a kernel built with `CONFIG_MEMCG` (since Linux 4.3)
+pub struct KPageCgroup {
+    reader: BufReader<FileWrapper>,
+}
+
+impl KPageCgroup {
+    /// Get a parser from default `/proc/kpagecgroup`
+    ///
+    /// Return `Err` if process is not running as root or don't have CAP_SYS_ADMIN
+    pub fn new() -> ProcResult<Self> {
+        Self::from_custom_root("/proc")
+    }
+
+    /// Get a parser from custom `/proc`
+    ///
+    /// Return `Err` if process is not running as root or don't have CAP_SYS_ADMIN
+    pub fn from_custom_root<P: AsRef<Path>>(root: P) -> ProcResult<Self> {
+        let mut path = root.as_ref().to_path_buf();
+        path.push("kpagecgroup");
+
+        let reader = BufReader::new(FileWrapper::open(path)?);
+
+        Ok(Self { reader })
+    }
+
+    /// Get the inode of the memory cgroup that the physical page at `pfn` is charged to
+    ///
+    /// Returns 0 if the page isn't charged to any cgroup.
+    ///
+    /// Return Err if pfn is not in RAM. See [crate::iomem()] for a list of valid physical RAM addresses
+    ///
+    /// See [crate::process::Process::pagemap] and [crate::process::MemoryPageFlags::get_page_frame_number]
+    pub fn get_cgroup_at_pfn(&mut self, pfn: Pfn) -> ProcResult<u64> {
+        self.get_cgroup_in_range(pfn, Pfn(pfn.0 + 1))
+            .map(|mut vec| vec.pop().unwrap())
+    }
+
+    /// Get the memory cgroup inodes for PFNs within `start` and `end` PFNs, `end` is excluded
+    ///
+    /// Return Err if any pfn is not in RAM. See [crate::iomem()] for a list of valid physical RAM addresses
+    ///
+    /// See [crate::process::Process::pagemap] and [crate::process::MemoryPageFlags::get_page_frame_number]
+    pub fn get_cgroup_in_range(&mut self, start: Pfn, end: Pfn) -> ProcResult<Vec<u64>> {
+        let mut result = Vec::with_capacity((end.0 - start.0) as usize);
+
+        let start_position = start.0 * size_of::<u64>() as u64;
+        self.reader.seek(SeekFrom::Start(start_position))?;
+
+        for _pfn in start.0..end.0 {
+            // Each entry is a 64 bits inode number
+            let mut buf = [0; size_of::<u64>()];
+
+            self.reader.read_exact(&mut buf)?;
+            result.push(u64::from_ne_bytes(buf));
+        }
+
+        Ok(result)
+    }
+}
+
+/// Resolves the cgroup inode numbers from [KPageCgroup] to cgroup paths
+///
+/// The cgroup hierarchy is walked once when the resolver is created; call
+/// [refresh()](CgroupResolver::refresh) to pick up cgroups that were created later.  The paths are
+/// in the same format as in `/proc/<pid>/cgroup` (for example `/system.slice/sshd.service`).
+#[derive(Debug, Clone)]
+pub struct CgroupResolver {
+    mount_point: PathBuf,
+    root: PathBuf,
+    paths: HashMap<u64, PathBuf>,
+}
+
+impl CgroupResolver {
+    /// Creates a resolver for the cgroup hierarchy that the memory controller is attached to
+    ///
+    /// This uses the `cgroup2` mount from the mountinfo of the current process if the memory
+    /// controller is available in it (listed in its `cgroup.controllers`), or else the cgroup v1
+    /// mount with the memory controller.  Returns [ProcError::NotFound] if neither is mounted.
+    pub fn new() -> ProcResult<Self> {
+        let mountinfo = Process::myself()?.mountinfo()?;
+        let is_memory_v2 = |m: &&MountInfo| {
+            m.fs_type == "cgroup2"
+                && std::fs::read_to_string(m.mount_point.join("cgroup.controllers"))
+                    .map(|controllers| controllers.split_whitespace().any(|c| c == "memory"))
+                    .unwrap_or(false)
+        };
+        let is_memory_v1 = |m: &&MountInfo| m.fs_type == "cgroup" && m.super_options.contains_key("memory");
+        let mount = mountinfo
+            .iter()
+            .find(is_memory_v2)
+            .or_else(|| mountinfo.iter().find(is_memory_v1))
+            .ok_or(ProcError::NotFound(None))?;
+        Self::from_mount_point(&mount.mount_point, &mount.root)
+    }
+
+    /// Creates a resolver for the cgroup hierarchy mounted at `mount_point`
+    ///
+    /// `root` is the path of the mounted directory within the hierarchy, as in
+    /// [MountInfo::root]; it's usually `/`.
+    pub fn from_mount_point<P: AsRef<Path>, R: AsRef<Path>>(mount_point: P, root: R) -> ProcResult<Self> {
+        let mut resolver = CgroupResolver {
+            mount_point: mount_point.as_ref().to_path_buf(),
+            root: root.as_ref().to_path_buf(),
+            paths: HashMap::new(),
+        };
+        resolver.refresh()?;
+        Ok(resolver)
+    }
+
+    /// Walks the cgroup hierarchy again
+    ///
+    /// Directories that can't be read (or that are removed during the walk) are skipped.
+    pub fn refresh(&mut self) -> ProcResult<()> {
+        let mut paths = HashMap::new();
+        let metadata = wrap_io_error!(self.mount_point, std::fs::metadata(&self.mount_point))?;
+        paths.insert(metadata.ino(), self.root.clone());
+
+        let mut stack = vec![(self.mount_point.clone(), self.root.clone())];
+        while let Some((dir, cgroup)) = stack.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                if !is_dir {
+                    continue;
+                }
+                if let Ok(metadata) = entry.metadata() {
+                    let path = cgroup.join(entry.file_name());
+                    paths.insert(metadata.ino(), path.clone());
+                    stack.push((entry.path(), path));
+                }
+            }
+        }
+
+        self.paths = paths;
+        Ok(())
+    }
+
+    /// Returns the path of the cgroup with the given inode number
+    ///
+    /// Returns `None` for inode 0 (pages that aren't charged to a cgroup), and for cgroups that
+    /// didn't exist when the hierarchy was walked.
+    pub fn resolve(&self, inode: u64) -> Option<&Path> {
+        self.paths.get(&inode).map(PathBuf::as_path)
+    }
+
+    /// Returns the directory where the cgroup hierarchy is mounted
+    pub fn mount_point(&self) -> &Path {
+        &self.mount_point
+    }
+
+    /// Returns the number of cgroups that were found
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns true if no cgroups were found
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WithCurrentSystemInfo;
+
+    #[test]
+    fn test_cgroup_resolver() {
+        let resolver = match CgroupResolver::new() {
+            Ok(resolver) => resolver,
+            Err(ProcError::NotFound(_)) => return,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(!resolver.is_empty());
+        let root = std::fs::metadata(resolver.mount_point()).unwrap().ino();
+        assert!(resolver.resolve(root).is_some());
+        assert!(resolver.resolve(0).is_none());
+
+        let mut kpagecgroup = match KPageCgroup::new() {
+            Ok(kpagecgroup) => kpagecgroup,
+            Err(ProcError::NotFound(_)) | Err(ProcError::PermissionDenied(_)) => return,
+            Err(e) => panic!("{}", e),
+        };
+        let ram = crate::iomem().unwrap();
+        if let Some((_, map)) = ram.iter().find(|(_, map)| map.name == "System RAM") {
+            let (start, end) = map.get_range().get();
+            let end = Pfn(end.0.min(start.0 + 1024));
+            let inodes = kpagecgroup.get_cgroup_in_range(start, end).unwrap();
+            assert_eq!(inodes.len() as u64, end.0 - start.0);
+        }
+    }
+}
@@ -1,11 +1,16 @@
-use crate::{process::Pfn, FileWrapper, ProcResult};
+use crate::{process::Pfn, FileWrapper, ProcResult, WithCurrentSystemInfo};
 
 use std::{
+    collections::BTreeMap,
+    fmt,
     io::{BufReader, Read, Seek, SeekFrom},
     mem::size_of,
     path::Path,
 };
 
+/// The number of PFNs read at once by [KPageFlags::summarize()]
+const SUMMARY_BATCH: u64 = 64 * 1024;
+
 pub use procfs_core::PhysicalPageFlags;
 
 /// Parse physical memory flags accessing `/proc/kpageflags`.
@@ -62,11 +67,166 @@ impl KPageFlags {
 
         Ok(page_infos)
     }
+
+    /// Counts the pages with each combination of flags within `start` (included) and `end`
+    /// (excluded) PFNs, like the kernel's `page-types` tool
+    ///
+    /// The file is read in large batches, so this is much faster than calling
+    /// [get_range_info()](Self::get_range_info) for each page.  Only the pages that match `filter`
+    /// are counted.
+    ///
+    /// Return Err if any PFN is not in RAM (see [crate::iomem()])
+    pub fn summarize(&mut self, start: Pfn, end: Pfn, filter: &PageFlagsFilter) -> ProcResult<PageTypesSummary> {
+        let mut summary = PageTypesSummary {
+            page_size: crate::page_size(),
+            ..Default::default()
+        };
+        self.summarize_into(start, end, filter, &mut summary)?;
+        Ok(summary)
+    }
+
+    /// Like [summarize()](Self::summarize), but for every "System RAM" range from [crate::iomem()]
+    ///
+    /// Reading `/proc/iomem` also requires root, otherwise all addresses are reported as zero.
+    pub fn summarize_system_ram(&mut self, filter: &PageFlagsFilter) -> ProcResult<PageTypesSummary> {
+        let mut summary = PageTypesSummary {
+            page_size: crate::page_size(),
+            ..Default::default()
+        };
+        for (indent, map) in crate::iomem()? {
+            if indent == 0 && map.name == "System RAM" {
+                let (start, end) = map.get_range().get();
+                self.summarize_into(start, end, filter, &mut summary)?;
+            }
+        }
+        Ok(summary)
+    }
+
+    fn summarize_into(
+        &mut self,
+        start: Pfn,
+        end: Pfn,
+        filter: &PageFlagsFilter,
+        summary: &mut PageTypesSummary,
+    ) -> ProcResult<()> {
+        const ENTRY_SIZE: usize = size_of::<PhysicalPageFlags>();
+
+        self.reader.seek(SeekFrom::Start(start.0 * ENTRY_SIZE as u64))?;
+        let mut buf = vec![0; SUMMARY_BATCH as usize * ENTRY_SIZE];
+        let mut pfn = start.0;
+        while pfn < end.0 {
+            let count = (end.0 - pfn).min(SUMMARY_BATCH) as usize;
+            let buf = &mut buf[..count * ENTRY_SIZE];
+            self.reader.read_exact(buf)?;
+            for entry in buf.chunks_exact(ENTRY_SIZE) {
+                let mut bytes = [0; ENTRY_SIZE];
+                bytes.copy_from_slice(entry);
+                let flags = PhysicalPageFlags::parse_info(u64::from_ne_bytes(bytes));
+                summary.total_pages += 1;
+                if filter.matches(flags) {
+                    summary.matched_pages += 1;
+                    *summary.counts.entry(flags & filter.mask).or_default() += 1;
+                }
+            }
+            pfn += count as u64;
+        }
+        Ok(())
+    }
+}
+
+/// Selects the pages to count in a [PageTypesSummary]
+///
+/// The default filter counts all pages, grouped by all of their flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageFlagsFilter {
+    /// Only count pages that have all of these flags
+    pub include: PhysicalPageFlags,
+    /// Only count pages that have none of these flags
+    pub exclude: PhysicalPageFlags,
+    /// The flags to group the pages by; other flags are ignored when counting
+    pub mask: PhysicalPageFlags,
+}
+
+impl Default for PageFlagsFilter {
+    fn default() -> Self {
+        PageFlagsFilter {
+            include: PhysicalPageFlags::empty(),
+            exclude: PhysicalPageFlags::empty(),
+            mask: PhysicalPageFlags::all(),
+        }
+    }
+}
+
+impl PageFlagsFilter {
+    /// Returns true if a page with these flags should be counted
+    pub fn matches(&self, flags: PhysicalPageFlags) -> bool {
+        flags.contains(self.include) && !flags.intersects(self.exclude)
+    }
+}
+
+/// The number of physical pages with each combination of flags
+///
+/// Created by [KPageFlags::summarize()] or [KPageFlags::summarize_system_ram()].  The `Display`
+/// implementation prints a table like the one from the kernel's `page-types` tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageTypesSummary {
+    /// The number of pages that were scanned
+    pub total_pages: u64,
+    /// The number of pages that matched the filter
+    pub matched_pages: u64,
+    /// The number of matching pages for each combination of flags
+    pub counts: BTreeMap<PhysicalPageFlags, u64>,
+    /// The size of a page, in bytes
+    pub page_size: u64,
+}
+
+impl PageTypesSummary {
+    /// Returns the number of matching pages that have all of the given flags
+    pub fn pages_with(&self, flags: PhysicalPageFlags) -> u64 {
+        self.counts
+            .iter()
+            .filter(|(f, _)| f.contains(flags))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Returns the combinations of flags, ordered by the number of pages (largest first)
+    pub fn by_count(&self) -> Vec<(PhysicalPageFlags, u64)> {
+        let mut ret: Vec<_> = self.counts.iter().map(|(f, c)| (*f, *c)).collect();
+        ret.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ret
+    }
+}
+
+impl fmt::Display for PageTypesSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mb = |pages: u64| pages * self.page_size / (1024 * 1024);
+        writeln!(f, "{:>18}  {:>12}  {:>8}  symbolic-flags", "flags", "page-count", "MB")?;
+        for (flags, count) in &self.counts {
+            let names: Vec<&str> = flags.iter_names().map(|(name, _)| name).collect();
+            writeln!(
+                f,
+                "{:#018x}  {:>12}  {:>8}  {}",
+                flags.bits(),
+                count,
+                mb(*count),
+                names.join(",")
+            )?;
+        }
+        write!(
+            f,
+            "{:>18}  {:>12}  {:>8}",
+            "total",
+            self.matched_pages,
+            mb(self.matched_pages)
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ProcError;
 
     #[test]
     fn test_kpageflags_parsing() {
@@ -74,4 +234,41 @@ mod tests {
         let info = PhysicalPageFlags::parse_info(pagemap_entry);
         assert!(info == PhysicalPageFlags::LOCKED);
     }
+
+    #[test]
+    fn test_page_flags_filter() {
+        let filter = PageFlagsFilter {
+            include: PhysicalPageFlags::LRU,
+            exclude: PhysicalPageFlags::ANON,
+            ..Default::default()
+        };
+        assert!(filter.matches(PhysicalPageFlags::LRU | PhysicalPageFlags::MMAP));
+        assert!(!filter.matches(PhysicalPageFlags::LRU | PhysicalPageFlags::ANON));
+        assert!(!filter.matches(PhysicalPageFlags::MMAP));
+        assert!(PageFlagsFilter::default().matches(PhysicalPageFlags::empty()));
+
+        let mut summary = PageTypesSummary {
+            page_size: 4096,
+            ..Default::default()
+        };
+        summary.counts.insert(PhysicalPageFlags::LRU, 256);
+        summary
+            .counts
+            .insert(PhysicalPageFlags::LRU | PhysicalPageFlags::MMAP, 512);
+        summary.matched_pages = 768;
+        assert_eq!(summary.pages_with(PhysicalPageFlags::LRU), 768);
+        assert_eq!(summary.by_count()[0].1, 512);
+        let table = summary.to_string();
+        assert!(table.contains("0x0000000000000820           512         2  LRU,MMAP"));
+        assert!(table.ends_with("total           768         3"));
+
+        let mut kpageflags = match KPageFlags::new() {
+            Ok(kpageflags) => kpageflags,
+            Err(ProcError::NotFound(_)) | Err(ProcError::PermissionDenied(_)) => return,
+            Err(e) => panic!("{}", e),
+        };
+        let summary = kpageflags.summarize(Pfn(0), Pfn(1024), &Default::default()).unwrap();
+        assert_eq!(summary.total_pages, 1024);
+        assert_eq!(summary.counts.values().sum::<u64>(), summary.matched_pages);
+    }
 }
@@ -194,6 +194,9 @@ pub use kpageflags::*;
 mod kpagecount;
 pub use kpagecount::*;
 
+mod kpagecgroup;
+pub use kpagecgroup::*;
+
 pub mod net;
 
 pub mod process;
@@ -504,6 +507,10 @@ impl Current for Uptime {
     const PATH: &'static str = "/proc/uptime";
 }
 
+impl Current for KernelSchedstat {
+    const PATH: &'static str = "/proc/schedstat";
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -610,6 +617,10 @@ mod tests {
 
         let diff = stat.total.idle as i64 - (stat.cpu_time.iter().map(|i| i.idle).sum::<u64>() as i64).abs();
         assert!(diff < 1000, "idle time difference too high: {}", diff);
+
+        assert!(stat.intr().is_some());
+        assert!(stat.softirq().is_some());
+        assert_eq!(stat.cpu_times().count(), stat.cpu_time.len());
     }
 
     #[test]
@@ -702,6 +713,26 @@ mod tests {
         for disk in super::diskstats().unwrap() {
             println!("{:?}", disk);
         }
+
+        let before = DiskStats::current().unwrap();
+        let after = DiskStats::current().unwrap();
+        for report in after.delta(&before, std::time::Duration::from_millis(10)) {
+            assert!(report.utilization >= 0.0 && report.utilization <= 100.0);
+        }
+
+        let folded = after.fold_partitions(&partitions().unwrap(), &Devices::current().unwrap());
+        assert!(folded.0.len() <= after.0.len());
+    }
+
+    #[test]
+    fn test_schedstat() {
+        // requires CONFIG_SCHEDSTATS
+        if !Path::new("/proc/schedstat").exists() {
+            return;
+        }
+        let schedstat = KernelSchedstat::current().unwrap();
+        assert!(!schedstat.cpus.is_empty());
+        println!("{:#?}", schedstat);
     }
 
     #[test]
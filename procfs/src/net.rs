@@ -53,8 +53,8 @@
 //!     }
 //! }
 //! ```
-use crate::ProcResult;
 use crate::{current_system_info, Current};
+use crate::{ProcError, ProcResult};
 pub use procfs_core::net::*;
 use procfs_core::FromReadSI;
 use std::collections::HashMap;
@@ -133,6 +133,55 @@ pub fn dev_status() -> ProcResult<HashMap<String, DeviceStatus>> {
     InterfaceDeviceStatus::current().map(|e| e.0)
 }
 
+impl super::Current for WirelessEntries {
+    const PATH: &'static str = "/proc/net/wireless";
+}
+
+/// Returns wireless statistics for all wireless interfaces
+///
+/// This data is from the `/proc/net/wireless` file, which only exists if the kernel was built
+/// with wireless extensions (`CONFIG_WEXT_PROC`).
+///
+/// Note that this returns information from the networking namespace of the
+/// current process.  If you want information for some other process, see
+/// [Process::wireless()](crate::process::Process::wireless())
+pub fn wireless() -> ProcResult<Vec<WirelessEntry>> {
+    WirelessEntries::current().map(|e| e.0)
+}
+
+impl super::Current for Inet6AddrEntries {
+    const PATH: &'static str = "/proc/net/if_inet6";
+}
+
+/// Returns the IPv6 addresses of all interfaces
+///
+/// This data is from the `/proc/net/if_inet6` file, which doesn't exist if IPv6 is disabled.
+///
+/// Note that this returns information from the networking namespace of the
+/// current process.  If you want information for some other process, see
+/// [Process::if_inet6()](crate::process::Process::if_inet6())
+pub fn if_inet6() -> ProcResult<Vec<Inet6AddrEntry>> {
+    Inet6AddrEntries::current().map(|e| e.0)
+}
+
+/// Returns the statistics, wireless statistics and IPv6 addresses of all interfaces
+///
+/// This combines the data from `/proc/net/dev`, `/proc/net/wireless` and `/proc/net/if_inet6`.
+/// If either of the last two files doesn't exist, the corresponding fields are left empty.  See
+/// [InterfaceDeviceStatus::join()].
+pub fn interfaces() -> ProcResult<Vec<InterfaceInfo>> {
+    let dev = InterfaceDeviceStatus::current()?;
+    let wireless = match WirelessEntries::current() {
+        Err(ProcError::NotFound(_)) => WirelessEntries(Vec::new()),
+        w => w?,
+    };
+    let inet6 = match Inet6AddrEntries::current() {
+        Err(ProcError::NotFound(_)) => Inet6AddrEntries(Vec::new()),
+        i => i?,
+    };
+    Ok(dev.join(&wireless, &inet6))
+}
+
 impl super::Current for RouteEntries {
     const PATH: &'static str = "/proc/net/route";
 }
@@ -226,6 +275,29 @@ mod tests {
         println!("{:#?}", status);
     }
 
+    #[test]
+    fn test_interfaces() {
+        let before = InterfaceDeviceStatus::current().unwrap();
+        let after = InterfaceDeviceStatus::current().unwrap();
+        let rates = after.rates_since(&before, std::time::Duration::from_millis(10));
+        assert!(rates.contains_key("lo"));
+
+        match wireless() {
+            Ok(entries) => println!("{:?}", entries),
+            Err(ProcError::NotFound(_)) => {}
+            Err(e) => panic!("{}", e),
+        }
+        match if_inet6() {
+            Ok(entries) => println!("{:?}", entries),
+            Err(ProcError::NotFound(_)) => {}
+            Err(e) => panic!("{}", e),
+        }
+
+        let interfaces = interfaces().unwrap();
+        assert!(interfaces.iter().any(|i| i.name == "lo"));
+        println!("{:#?}", interfaces);
+    }
+
     #[test]
     fn test_arp() {
         for entry in arp().unwrap() {
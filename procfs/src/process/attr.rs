@@ -0,0 +1,87 @@
+use super::Process;
+use crate::{write_value, FileWrapper, ProcError, ProcResult};
+use procfs_core::process::{SecurityAttr, SecurityContext};
+use std::io::Read;
+use std::path::Path;
+
+impl Process {
+    /// Returns the raw contents of one of the files in the `/proc/<pid>/attr/` directory
+    ///
+    /// Returns `Ok(None)` if the attribute is not set, or if no LSM that supports this attribute
+    /// is active.
+    ///
+    /// (since Linux 2.6.0)
+    pub fn security_attr(&self, attr: SecurityAttr) -> ProcResult<Option<String>> {
+        self.read_security_attr(Path::new("attr").join(attr.file_name()))
+    }
+
+    /// Returns the current security context of this process
+    ///
+    /// This detects the active Linux Security Module (LSM) using the LSM-specific subdirectories of
+    /// `/proc/<pid>/attr/` when they are present (since Linux 5.1), and otherwise by looking at the
+    /// format of `/proc/<pid>/attr/current`.
+    ///
+    /// Returns `Ok(None)` if no LSM that assigns security contexts to processes is active.
+    pub fn security_context(&self) -> ProcResult<Option<SecurityContext>> {
+        if let Some(s) = self.read_security_attr("attr/apparmor/current")? {
+            return Ok(Some(SecurityContext::AppArmor(s.parse()?)));
+        }
+        if let Some(s) = self.read_security_attr("attr/smack/current")? {
+            return Ok(Some(SecurityContext::Smack(s)));
+        }
+        match self.security_attr(SecurityAttr::Current)? {
+            Some(s) => Ok(Some(SecurityContext::from_current(&s)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the security context that this process will be assigned on its next `execve(2)`,
+    /// by writing to `/proc/<pid>/attr/exec`
+    ///
+    /// For AppArmor, this changes to the given profile on exec (the mode is ignored).
+    ///
+    /// A process can only set its own security attributes, so this must be called on
+    /// [Process::myself()].
+    pub fn set_exec_security_context(&self, context: &SecurityContext) -> ProcResult<()> {
+        let value = match context {
+            SecurityContext::AppArmor(aa) => format!("exec {}", aa.profile),
+            other => other.to_string(),
+        };
+        write_value(self.root.join("attr/exec"), value)
+    }
+
+    /// Sets the security context that will be assigned to files created by this process, by
+    /// writing to `/proc/<pid>/attr/fscreate`
+    ///
+    /// AppArmor does not support this attribute.
+    ///
+    /// A process can only set its own security attributes, so this must be called on
+    /// [Process::myself()].
+    pub fn set_fscreate_security_context(&self, context: &SecurityContext) -> ProcResult<()> {
+        if let SecurityContext::AppArmor(_) = context {
+            return Err(ProcError::Other("AppArmor does not support attr/fscreate".to_string()));
+        }
+        write_value(self.root.join("attr/fscreate"), context)
+    }
+
+    fn read_security_attr<P: AsRef<Path>>(&self, path: P) -> ProcResult<Option<String>> {
+        let mut s = String::new();
+        let result = FileWrapper::open_at(&self.root, &self.fd, path).and_then(|mut f| f.read_to_string(&mut s));
+        match result.map_err(ProcError::from) {
+            Ok(_) => {}
+            // the LSM-specific subdirectory doesn't exist
+            Err(ProcError::NotFound(_)) if self.is_alive() => return Ok(None),
+            // no active LSM supports this attribute
+            Err(ProcError::Io(e, _)) if e.raw_os_error() == Some(rustix::io::Errno::INVAL.raw_os_error()) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        }
+        let s = s.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+        if s.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(s.to_string()))
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use super::Process;
+use crate::{write_file, write_value, FileWrapper, ProcError, ProcResult};
+use procfs_core::process::{IdMap, SetGroups};
+use std::io::Read;
+
+impl Process {
+    /// Returns the user ID mapping of the user namespace that this process is in, from the
+    /// `/proc/<pid>/uid_map` file
+    ///
+    /// The mapping is relative to the user namespace of the calling process.  An empty map means
+    /// that the mapping has not been written yet.
+    ///
+    /// (since Linux 3.5)
+    pub fn uid_map(&self) -> ProcResult<IdMap> {
+        self.read("uid_map")
+    }
+
+    /// Returns the group ID mapping of the user namespace that this process is in, from the
+    /// `/proc/<pid>/gid_map` file
+    ///
+    /// See [Process::uid_map()] for details.
+    ///
+    /// (since Linux 3.5)
+    pub fn gid_map(&self) -> ProcResult<IdMap> {
+        self.read("gid_map")
+    }
+
+    /// Returns the project ID mapping of the user namespace that this process is in, from the
+    /// `/proc/<pid>/projid_map` file
+    ///
+    /// See [Process::uid_map()] for details.
+    ///
+    /// (since Linux 3.7)
+    pub fn projid_map(&self) -> ProcResult<IdMap> {
+        self.read("projid_map")
+    }
+
+    /// Returns whether `setgroups(2)` is permitted in the user namespace that this process is in,
+    /// from the `/proc/<pid>/setgroups` file
+    ///
+    /// (since Linux 3.19)
+    pub fn setgroups(&self) -> ProcResult<SetGroups> {
+        let mut file = FileWrapper::open_at(&self.root, &self.fd, "setgroups")?;
+        let mut s = String::new();
+        file.read_to_string(&mut s)?;
+        Ok(s.parse()?)
+    }
+
+    /// Writes the user ID mapping for the user namespace that this process is in
+    ///
+    /// The kernel only allows a map to be written once, in a single `write(2)`, and enforces a
+    /// number of other rules (see `user_namespaces(7)`).  This function checks for these ahead of
+    /// time with [IdMap::validate()], and returns a `ProcError::Other` if the map is invalid or
+    /// has already been written.
+    ///
+    /// Note that this will still fail with `ProcError::PermissionDenied` if the caller lacks the
+    /// privileges needed to create the mapping.
+    pub fn set_uid_map(&self, map: &IdMap) -> ProcResult<()> {
+        self.write_id_map("uid_map", self.uid_map()?, map)
+    }
+
+    /// Writes the group ID mapping for the user namespace that this process is in
+    ///
+    /// See [Process::set_uid_map()] for details.  An unprivileged process must call
+    /// [Process::deny_setgroups()] before writing the group ID mapping.
+    pub fn set_gid_map(&self, map: &IdMap) -> ProcResult<()> {
+        self.write_id_map("gid_map", self.gid_map()?, map)
+    }
+
+    /// Writes the project ID mapping for the user namespace that this process is in
+    ///
+    /// See [Process::set_uid_map()] for details.
+    pub fn set_projid_map(&self, map: &IdMap) -> ProcResult<()> {
+        self.write_id_map("projid_map", self.projid_map()?, map)
+    }
+
+    /// Denies the use of `setgroups(2)` in the user namespace that this process is in
+    ///
+    /// This must be done before the group ID mapping is written, and is required before an
+    /// unprivileged process can write the group ID mapping.  Once denied, `setgroups(2)` can not
+    /// be allowed again in this user namespace.
+    ///
+    /// (since Linux 3.19)
+    pub fn deny_setgroups(&self) -> ProcResult<()> {
+        if !self.gid_map()?.is_empty() {
+            return Err(ProcError::Other(
+                "setgroups can not be changed after gid_map has been written".to_string(),
+            ));
+        }
+        write_value(self.root.join("setgroups"), SetGroups::Deny)
+    }
+
+    fn write_id_map(&self, name: &str, current: IdMap, map: &IdMap) -> ProcResult<()> {
+        if !current.is_empty() {
+            return Err(ProcError::Other(format!("{} has already been written", name)));
+        }
+        map.validate()?;
+        // The kernel requires the whole map to be written at once, and to fit in a single page
+        let buf = map.to_string();
+        if buf.len() as u64 >= crate::page_size() {
+            return Err(ProcError::Other(format!(
+                "{} is too large ({} bytes) to be written",
+                name,
+                buf.len()
+            )));
+        }
+        write_file(self.root.join(name), buf)
+    }
+}
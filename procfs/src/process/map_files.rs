@@ -0,0 +1,126 @@
+use super::{MemoryMap, Process};
+use crate::{FileWrapper, ProcError, ProcResult};
+use rustix::fd::OwnedFd;
+use rustix::fs::{Mode, OFlags};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
+
+impl Process {
+    /// Returns an iterator over the file-backed memory mappings of this process, from the
+    /// `/proc/<pid>/map_files/` directory.
+    ///
+    /// Each entry has the same address range as the corresponding [MemoryMap](super::MemoryMap)
+    /// from [Process::maps()], and so the two can be matched up.
+    ///
+    /// Reading the link targets requires the `CAP_SYS_ADMIN` capability (before Linux 4.3) or
+    /// `PTRACE_MODE_READ_FSCREDS` access to the process (since Linux 4.3).
+    ///
+    /// (since Linux 3.3 and requires CONFIG_CHECKPOINT_RESTORE)
+    pub fn map_files(&self) -> ProcResult<MapFilesIter> {
+        let dir_fd = wrap_io_error!(
+            self.root.join("map_files"),
+            rustix::fs::openat(
+                &self.fd,
+                "map_files",
+                OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
+                Mode::empty()
+            )
+        )?;
+        let dir = wrap_io_error!(self.root.join("map_files"), rustix::fs::Dir::read_from(&dir_fd))?;
+        Ok(MapFilesIter {
+            inner: dir,
+            inner_fd: dir_fd,
+            root: self.root.join("map_files"),
+        })
+    }
+
+    /// Opens the file that backs the memory mapping at the given address range
+    ///
+    /// The address range must exactly match the range of a mapping, as given by
+    /// [MemoryMap::address](super::MemoryMap::address) or [MapFile::address].  Because the file is
+    /// opened through the mapping, this works even if the file has since been deleted or replaced
+    /// on disk.
+    ///
+    /// This requires the `CAP_SYS_ADMIN` capability.
+    ///
+    /// (since Linux 3.3 and requires CONFIG_CHECKPOINT_RESTORE)
+    pub fn open_map_file(&self, address: (u64, u64)) -> ProcResult<File> {
+        let path = PathBuf::from("map_files").join(format!("{:x}-{:x}", address.0, address.1));
+        Ok(FileWrapper::open_at(&self.root, &self.fd, path)?.inner())
+    }
+
+    /// Opens the file that backs a memory mapping from [Process::maps()]
+    ///
+    /// This is the same as [open_map_file()](Process::open_map_file) with the address range of
+    /// `map`.  Anonymous mappings aren't backed by a file, and return [ProcError::NotFound].
+    ///
+    /// This requires the `CAP_SYS_ADMIN` capability.
+    ///
+    /// (since Linux 3.3 and requires CONFIG_CHECKPOINT_RESTORE)
+    pub fn open_mapping(&self, map: &MemoryMap) -> ProcResult<File> {
+        self.open_map_file(map.address)
+    }
+}
+
+/// A file-backed memory mapping, from the `/proc/<pid>/map_files/` directory
+///
+/// See [Process::map_files()]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct MapFile {
+    /// The address range of the mapping
+    pub address: (u64, u64),
+    /// The path of the file backing the mapping
+    ///
+    /// If the file has been deleted, this will end with `" (deleted)"`.
+    pub target: PathBuf,
+}
+
+/// The result of [`Process::map_files`], iterates over all file-backed mappings in a process
+#[derive(Debug)]
+pub struct MapFilesIter {
+    inner: rustix::fs::Dir,
+    inner_fd: OwnedFd,
+    root: PathBuf,
+}
+
+impl std::iter::Iterator for MapFilesIter {
+    type Item = ProcResult<MapFile>;
+    fn next(&mut self) -> Option<ProcResult<MapFile>> {
+        loop {
+            let entry = match self.inner.next()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(io::Error::from(e).into())),
+            };
+            let name = entry.file_name().to_string_lossy();
+            let Some((start, end)) = name.split_once('-') else {
+                // skip "." and ".."
+                continue;
+            };
+            let (Ok(start), Ok(end)) = (u64::from_str_radix(start, 16), u64::from_str_radix(end, 16)) else {
+                continue;
+            };
+
+            match wrap_io_error!(
+                self.root.join(name.as_ref()),
+                rustix::fs::readlinkat(&self.inner_fd, entry.file_name(), Vec::new())
+            ) {
+                Ok(target) => {
+                    return Some(Ok(MapFile {
+                        address: (start, end),
+                        target: PathBuf::from(OsString::from_vec(target.into_bytes())),
+                    }))
+                }
+                // the mapping was removed since we started iterating
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Some(Err(ProcError::from(e))),
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use super::{Auxv, MMPermissions, MemoryMap, Process};
+use crate::{ProcError, ProcResult};
+use std::ffi::{CString, OsString};
+use std::fmt;
+use std::fs::File;
+use std::ops::Range;
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::fs::FileExt;
+use std::path::PathBuf;
+
+/// The number of bytes read at once by [ProcessMemory::search()]
+const SEARCH_CHUNK: usize = 1024 * 1024;
+
+/// The reason that the memory of a process couldn't be read
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MemoryError {
+    /// The address isn't in any mapping of the process
+    Unmapped { address: u64 },
+    /// The address is in a mapping that isn't readable
+    NotReadable { address: u64, permissions: MMPermissions },
+    /// The address is in a readable mapping, but the kernel refused to read it
+    ///
+    /// This happens for special mappings such as `[vvar]`, for pages of a file mapping that are
+    /// past the end of the file, and when the mapping was removed after the maps were read (see
+    /// [ProcessMemory::refresh()]).
+    Inaccessible { address: u64 },
+    /// No nul byte was found in the first `max` bytes of a C string
+    NotTerminated { address: u64, max: usize },
+    /// Some other error, such as the process having exited
+    Other(ProcError),
+}
+
+impl fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MemoryError::Unmapped { address } => write!(f, "Address {:#x} is not mapped", address),
+            MemoryError::NotReadable { address, permissions } => write!(
+                f,
+                "Address {:#x} is in a mapping that is not readable ({})",
+                address,
+                permissions.as_str()
+            ),
+            MemoryError::Inaccessible { address } => write!(f, "Address {:#x} can't be read", address),
+            MemoryError::NotTerminated { address, max } => {
+                write!(f, "No nul byte in the {} bytes at {:#x}", max, address)
+            }
+            MemoryError::Other(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+impl From<ProcError> for MemoryError {
+    fn from(e: ProcError) -> Self {
+        MemoryError::Other(e)
+    }
+}
+
+/// The strings that the auxiliary vector of a process points to
+///
+/// Created by [ProcessMemory::auxv_strings()].  Each field is `None` if the auxiliary vector
+/// doesn't have the matching entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AuxvStrings {
+    /// The hardware platform (`AT_PLATFORM`), such as `x86_64`
+    pub platform: Option<String>,
+    /// The real platform (`AT_BASE_PLATFORM`), on architectures where it can differ from the
+    /// platform (such as PowerPC)
+    pub base_platform: Option<String>,
+    /// The pathname that was passed to `execve(2)` (`AT_EXECFN`)
+    ///
+    /// This may be relative, and may differ from [Process::exe()] when the program was started
+    /// through a symlink.
+    pub execfn: Option<PathBuf>,
+}
+
+/// Reads the memory of a process through `/proc/<pid>/mem`, checking addresses against its mappings
+///
+/// Unlike [Process::mem()], reads are only done within mappings that are readable according to
+/// [Process::maps()], and failures are reported as a [MemoryError] that says why the address
+/// couldn't be read.  The mappings are read once when this is created; call
+/// [refresh()](ProcessMemory::refresh) if the process may have changed its mappings since.
+///
+/// Opening the memory of another process requires PTRACE_MODE_ATTACH_FSCREDS access to it.
+///
+/// # Example
+///
+/// ```rust
+/// use procfs::process::Process;
+///
+/// let value: u64 = 0x1122334455667788;
+/// let process = Process::myself().unwrap();
+/// let memory = process.memory().unwrap();
+/// let mut buf = [0; 8];
+/// memory.read_at(&value as *const u64 as u64, &mut buf).unwrap();
+/// assert_eq!(u64::from_ne_bytes(buf), value);
+/// ```
+#[derive(Debug)]
+pub struct ProcessMemory<'a> {
+    process: &'a Process,
+    file: File,
+    maps: Vec<MemoryMap>,
+}
+
+impl Process {
+    /// Opens the memory of this process for reading
+    ///
+    /// See [ProcessMemory].
+    pub fn memory(&self) -> ProcResult<ProcessMemory<'_>> {
+        let mut memory = ProcessMemory {
+            process: self,
+            file: self.mem()?,
+            maps: Vec::new(),
+        };
+        memory.refresh()?;
+        Ok(memory)
+    }
+}
+
+impl ProcessMemory<'_> {
+    /// Reads the mappings of the process again
+    pub fn refresh(&mut self) -> ProcResult<()> {
+        let mut maps = self.process.maps()?.0;
+        maps.sort_by_key(|m| m.address.0);
+        self.maps = maps;
+        Ok(())
+    }
+
+    /// The mappings of the process, sorted by address
+    pub fn maps(&self) -> &[MemoryMap] {
+        &self.maps
+    }
+
+    /// Returns the mapping that contains the given address
+    pub fn map_at(&self, address: u64) -> Option<&MemoryMap> {
+        let index = self.maps.partition_point(|m| m.address.1 <= address);
+        self.maps.get(index).filter(|m| m.address.0 <= address)
+    }
+
+    /// Checks that the `len` bytes at `address` are all in readable mappings
+    pub fn check_readable(&self, address: u64, len: usize) -> Result<(), MemoryError> {
+        let end = address.saturating_add(len as u64);
+        let mut current = address;
+        while current < end {
+            let map = self.map_at(current).ok_or(MemoryError::Unmapped { address: current })?;
+            if !map.perms.contains(MMPermissions::READ) {
+                return Err(MemoryError::NotReadable {
+                    address: current,
+                    permissions: map.perms,
+                });
+            }
+            current = map.address.1;
+        }
+        Ok(())
+    }
+
+    /// Fills `buf` with the memory at `address`
+    pub fn read_at(&self, address: u64, buf: &mut [u8]) -> Result<(), MemoryError> {
+        self.check_readable(address, buf.len())?;
+        self.file
+            .read_exact_at(buf, address)
+            .map_err(|e| map_read_error(e, address))
+    }
+
+    /// Reads the `len` bytes at `address`
+    pub fn read_vec(&self, address: u64, len: usize) -> Result<Vec<u8>, MemoryError> {
+        let mut buf = vec![0; len];
+        self.read_at(address, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads several ranges of memory
+    ///
+    /// All ranges are checked before anything is read, so if any of them isn't readable, nothing is
+    /// read.
+    pub fn read_vectored(&self, ranges: &[Range<u64>]) -> Result<Vec<Vec<u8>>, MemoryError> {
+        for range in ranges {
+            self.check_readable(range.start, range.end.saturating_sub(range.start) as usize)?;
+        }
+        ranges
+            .iter()
+            .map(|range| self.read_vec(range.start, range.end.saturating_sub(range.start) as usize))
+            .collect()
+    }
+
+    /// Reads a value of type `T` at `address`
+    ///
+    /// The address doesn't need to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// Any bit pattern must be a valid value of `T` (as for plain integers and structs made of
+    /// them).  Types such as `bool`, `char`, enums and references must not be used.
+    pub unsafe fn read_struct<T: Copy>(&self, address: u64) -> Result<T, MemoryError> {
+        let buf = self.read_vec(address, std::mem::size_of::<T>())?;
+        Ok(std::ptr::read_unaligned(buf.as_ptr() as *const T))
+    }
+
+    /// Reads a nul-terminated string of at most `max` bytes (excluding the nul byte) at `address`
+    ///
+    /// The string is read one page at a time, so it can end right before an unreadable page.
+    pub fn read_c_string(&self, address: u64, max: usize) -> Result<CString, MemoryError> {
+        let page_size = crate::page_size();
+        let mut bytes = Vec::new();
+        let mut current = address;
+        // look at one more byte than `max`, for the nul byte
+        while bytes.len() <= max {
+            let to_page_end = page_size - current % page_size;
+            let len = to_page_end.min((max + 1 - bytes.len()) as u64) as usize;
+            let chunk = self.read_vec(current, len)?;
+            if let Some(nul) = chunk.iter().position(|b| *b == 0) {
+                bytes.extend_from_slice(&chunk[..nul]);
+                // there are no nul bytes left in `bytes`
+                return Ok(CString::new(bytes).unwrap());
+            }
+            bytes.extend_from_slice(&chunk);
+            current += len as u64;
+        }
+        Err(MemoryError::NotTerminated { address, max })
+    }
+
+    /// Reads the strings that the `AT_PLATFORM`, `AT_BASE_PLATFORM` and `AT_EXECFN` entries of an
+    /// auxiliary vector point to
+    ///
+    /// `auxv` must be the auxiliary vector of the same process (see [Process::auxiliary_vector()]).
+    pub fn auxv_strings(&self, auxv: &Auxv) -> Result<AuxvStrings, MemoryError> {
+        // the strings are at most PATH_MAX bytes long
+        const MAX: usize = 4096;
+        let read = |address: Option<u64>| address.map(|a| self.read_c_string(a, MAX)).transpose();
+
+        Ok(AuxvStrings {
+            platform: read(auxv.platform())?.map(|s| s.to_string_lossy().into_owned()),
+            base_platform: read(auxv.base_platform())?.map(|s| s.to_string_lossy().into_owned()),
+            execfn: read(auxv.execfn())?.map(|s| PathBuf::from(OsString::from_vec(s.into_bytes()))),
+        })
+    }
+
+    /// Returns the addresses where `pattern` is found in any readable mapping
+    ///
+    /// Mappings (or parts of mappings) that the kernel refuses to read are skipped.
+    pub fn search(&self, pattern: &[u8]) -> Result<Vec<u64>, MemoryError> {
+        let mut found = Vec::new();
+        for map in &self.maps {
+            if map.perms.contains(MMPermissions::READ) {
+                found.extend(self.search_range(map.address.0..map.address.1, pattern)?);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns the addresses where `pattern` is found within `range`
+    ///
+    /// The range is read in chunks, so large mappings can be searched without large allocations.
+    /// Returns an error if the range isn't in readable mappings, but pages that the kernel refuses
+    /// to read are skipped.
+    pub fn search_range(&self, range: Range<u64>, pattern: &[u8]) -> Result<Vec<u64>, MemoryError> {
+        let mut found = Vec::new();
+        if pattern.is_empty() {
+            return Ok(found);
+        }
+        self.check_readable(range.start, range.end.saturating_sub(range.start) as usize)?;
+        let page_size = crate::page_size();
+        let mut buf = vec![0; SEARCH_CHUNK.max(pattern.len())];
+        let mut start = range.start;
+        while start < range.end {
+            let len = (range.end - start).min(buf.len() as u64) as usize;
+            // a short read means that the kernel stopped at a page that it refused to read
+            let read = match self.file.read_at(&mut buf[..len], start) {
+                Ok(read) => read,
+                Err(e) => match map_read_error(e, start) {
+                    MemoryError::Inaccessible { .. } => 0,
+                    e => return Err(e),
+                },
+            };
+            if read == 0 {
+                // skip the page that can't be read
+                start = (start / page_size + 1) * page_size;
+                continue;
+            }
+
+            found.extend(
+                buf[..read]
+                    .windows(pattern.len())
+                    .enumerate()
+                    .filter(|(_, window)| *window == pattern)
+                    .map(|(offset, _)| start + offset as u64),
+            );
+            if start + read as u64 >= range.end || read < pattern.len() {
+                start += read as u64;
+            } else {
+                // overlap the chunks so that matches across chunks are found
+                start += (read - pattern.len() + 1) as u64;
+            }
+        }
+        Ok(found)
+    }
+}
+
+fn map_read_error(e: std::io::Error, address: u64) -> MemoryError {
+    if rustix::io::Errno::from_io_error(&e) == Some(rustix::io::Errno::IO) {
+        MemoryError::Inaccessible { address }
+    } else {
+        ProcError::from(e).into()
+    }
+}
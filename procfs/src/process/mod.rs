@@ -84,12 +84,39 @@ use std::str::FromStr;
 
 mod namespaces;
 
+mod id_map;
+
+mod attr;
+
+mod pidfd;
+
 mod task;
 pub use task::*;
 
 mod pagemap;
 pub use pagemap::*;
 
+mod map_files;
+pub use map_files::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+mod tree;
+pub use tree::*;
+
+mod usage;
+pub use usage::*;
+
+mod working_set;
+pub use working_set::*;
+
+mod residency;
+pub use residency::*;
+
+mod memory;
+pub use memory::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -177,6 +204,16 @@ impl FDInfo {
     pub fn mode(&self) -> FDPermissions {
         FDPermissions::from_bits_truncate(self.mode)
     }
+
+    /// Gets more details about this file descriptor, from the `/proc/<pid>/fdinfo/<fd>` file
+    ///
+    /// `process` must be the process that this file descriptor belongs to.  This is the same as
+    /// [Process::fdinfo()].
+    ///
+    /// (since Linux 2.6.22)
+    pub fn details(&self, process: &Process) -> ProcResult<FDDetails> {
+        process.fdinfo(self.fd)
+    }
 }
 
 impl std::fmt::Debug for FDInfo {
@@ -209,6 +246,7 @@ pub struct Process {
     fd: OwnedFd,
     pub pid: i32,
     pub(crate) root: PathBuf,
+    pidfd: Option<OwnedFd>,
 }
 
 /// Methods for constructing a new `Process` object.
@@ -250,7 +288,12 @@ impl Process {
             None => return Err(ProcError::NotFound(Some(root))),
         };
 
-        Ok(Process { fd: file, pid, root })
+        Ok(Process {
+            fd: file,
+            pid,
+            root,
+            pidfd: None,
+        })
     }
 
     /// Returns a `Process` for the currently running process.
@@ -422,6 +465,20 @@ impl Process {
         FromRead::from_read(FileWrapper::open_at(&self.root, &self.fd, "smaps_rollup")?)
     }
 
+    /// Returns the NUMA memory policy and per-node page allocation of each memory mapping, based
+    /// on the `/proc/pid/numa_maps` file.
+    ///
+    /// The end address of each mapping is filled in from the `/proc/pid/maps` file, so that the
+    /// results can be matched up against [Process::maps()] or [Process::smaps()].
+    ///
+    /// (since Linux 2.6.14 and requires CONFIG_NUMA)
+    pub fn numa_maps(&self) -> ProcResult<NumaMaps> {
+        let maps = self.maps()?;
+        let mut numa_maps: NumaMaps = self.read("numa_maps")?;
+        numa_maps.fill_ranges(&maps);
+        Ok(numa_maps)
+    }
+
     /// Returns the [MountStat] data for this process's mount namespace.
     pub fn mountstats(&self) -> ProcResult<MountStats> {
         self.read("mountstats")
@@ -501,6 +558,16 @@ impl Process {
         FDInfo::from_process_at(&self.root, self.fd.as_fd(), path, fd)
     }
 
+    /// Returns details about an open file descriptor, from the `/proc/<pid>/fdinfo/<fd>` file
+    ///
+    /// This includes the file offset and open flags, as well as type-specific information for
+    /// things like eventfds, epoll instances, inotify watches and timerfds.
+    ///
+    /// (since Linux 2.6.22)
+    pub fn fdinfo(&self, fd: i32) -> ProcResult<FDDetails> {
+        self.read(PathBuf::from("fdinfo").join(fd.to_string()))
+    }
+
     /// Lists which memory segments are written to the core dump in the event that a core dump is performed.
     ///
     /// By default, the following bits are set:
@@ -520,6 +587,18 @@ impl Process {
         Ok(Some(expect!(CoredumpFlags::from_bits(flags))))
     }
 
+    /// Sets which memory segments are written to the core dump of this process
+    ///
+    /// The new value is inherited by children created with `fork(2)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcError::PermissionDenied` if the caller isn't allowed to `ptrace(2)` the process.
+    pub fn set_coredump_filter(&self, flags: CoredumpFlags) -> ProcResult<()> {
+        // the kernel parses this value with automatic base detection, so it must have a 0x prefix
+        write_value(self.root.join("coredump_filter"), format!("{:#x}", flags.bits()))
+    }
+
     /// Gets the process's autogroup membership
     ///
     /// (since Linux 2.6.38 and requires CONFIG_SCHED_AUTOGROUP)
@@ -530,35 +609,65 @@ impl Process {
         Ok(s)
     }
 
-    /// Get the process's auxiliary vector
+    /// Sets the nice value of the process's autogroup
     ///
-    /// (since 2.6.0-test7)
-    pub fn auxv(&self) -> ProcResult<HashMap<u64, u64>> {
-        let mut file = FileWrapper::open_at(&self.root, &self.fd, "auxv")?;
-        let mut map = HashMap::new();
-
-        let mut buf = Vec::new();
-        let bytes_read = file.read_to_end(&mut buf)?;
-        if bytes_read == 0 {
-            // some kernel processes won't have any data for their auxv file
-            return Ok(map);
+    /// This affects how CPU time is shared between the autogroup of this process and other
+    /// autogroups, see `sched(7)` for details.  The nice value must be in the range `-20..=19`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcError::Other` if `nice` is out of range, and `ProcError::PermissionDenied` if
+    /// the caller is not allowed to lower the nice value (this requires the `CAP_SYS_NICE`
+    /// capability, or a suitable `RLIMIT_NICE`).
+    ///
+    /// (since Linux 2.6.38 and requires CONFIG_SCHED_AUTOGROUP)
+    pub fn set_autogroup_nice(&self, nice: i8) -> ProcResult<()> {
+        if !(-20..=19).contains(&nice) {
+            return Err(ProcError::Other(format!(
+                "autogroup nice value {} is outside the -20..=19 range",
+                nice
+            )));
         }
-        buf.truncate(bytes_read);
-        let mut file = std::io::Cursor::new(buf);
+        write_value(self.root.join("autogroup"), nice)
+    }
 
-        let mut buf = 0usize.to_ne_bytes();
-        loop {
-            file.read_exact(&mut buf)?;
-            let key = usize::from_ne_bytes(buf) as u64;
-            file.read_exact(&mut buf)?;
-            let value = usize::from_ne_bytes(buf) as u64;
-            if key == 0 && value == 0 {
-                break;
-            }
-            map.insert(key, value);
+    /// Sets the command name of the main thread of this process, by writing to the
+    /// `/proc/[pid]/comm` file
+    ///
+    /// The kernel silently truncates names that are longer than 15 bytes.  A thread can only
+    /// change the name of threads in its own thread group, see also [Task::set_comm()].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcError::Other` if `comm` contains a nul byte, and `ProcError::Io` with
+    /// [`ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput) if the process is not in
+    /// the same thread group as the caller.
+    ///
+    /// (since Linux 2.6.33)
+    pub fn set_comm(&self, comm: &str) -> ProcResult<()> {
+        if comm.contains('\0') {
+            return Err(ProcError::Other(format!("comm {:?} contains a nul byte", comm)));
         }
+        write_value(self.root.join("comm"), comm)
+    }
 
-        Ok(map)
+    /// Get the process's auxiliary vector
+    ///
+    /// See [auxiliary_vector()](Process::auxiliary_vector) for a typed version.
+    ///
+    /// (since 2.6.0-test7)
+    pub fn auxv(&self) -> ProcResult<HashMap<u64, u64>> {
+        Ok(self.auxiliary_vector()?.entries.into_iter().collect())
+    }
+
+    /// Get the process's auxiliary vector, with typed accessors for the known keys
+    ///
+    /// The strings that some of the entries point to can be read with
+    /// [ProcessMemory::auxv_strings()].
+    ///
+    /// (since 2.6.0-test7)
+    pub fn auxiliary_vector(&self) -> ProcResult<Auxv> {
+        FromRead::from_read(FileWrapper::open_at(&self.root, &self.fd, "auxv")?)
     }
 
     /// Gets the symbolic name corresponding to the location in the kernel where the process is sleeping.
@@ -571,11 +680,47 @@ impl Process {
         Ok(s)
     }
 
+    /// Returns the kernel stack of the main thread of this process, from the `/proc/[pid]/stack` file.
+    ///
+    /// This requires the `CAP_SYS_ADMIN` capability, and will return `ProcError::PermissionDenied`
+    /// otherwise.  See also [Task::stack()].
+    ///
+    /// (since Linux 2.6.29 and requires CONFIG_STACKTRACE)
+    pub fn stack(&self) -> ProcResult<KernelStack> {
+        self.read("stack")
+    }
+
     /// Return the `Status` for this process, based on the `/proc/[pid]/status` file.
     pub fn status(&self) -> ProcResult<Status> {
         self.read("status")
     }
 
+    /// Returns the set of CPUs on which the main thread of this process may run
+    ///
+    /// This is taken from the `/proc/[pid]/status` file.  Each thread can have a different
+    /// affinity, see [Task::cpu_affinity()].
+    ///
+    /// (since Linux 2.6.24)
+    pub fn cpu_affinity(&self) -> ProcResult<CpuSet> {
+        match self.status()?.cpu_affinity() {
+            Some(set) => Ok(set),
+            None => Err(ProcError::Incomplete(Some(self.root.join("status")))),
+        }
+    }
+
+    /// Returns the path of the process's cpuset, from the `/proc/[pid]/cpuset` file.
+    ///
+    /// The path is relative to the root of the cpuset filesystem (or of the cgroup v2 hierarchy
+    /// when cgroup v2 is in use).  See `cpuset(7)` for details.
+    ///
+    /// (since Linux 2.6.12 and requires CONFIG_CPUSETS)
+    pub fn cpuset(&self) -> ProcResult<PathBuf> {
+        let mut file = FileWrapper::open_at(&self.root, &self.fd, "cpuset")?;
+        let mut s = String::new();
+        file.read_to_string(&mut s)?;
+        Ok(PathBuf::from(s.trim_end_matches('\n')))
+    }
+
     /// Returns the status info from `/proc/[pid]/stat`.
     pub fn stat(&self) -> ProcResult<Stat> {
         self.read("stat")
@@ -625,6 +770,78 @@ impl Process {
         write_value(path, new_oom_score_adj)
     }
 
+    /// The legacy OOM-killer adjustment value of this process
+    ///
+    /// Values range from -16 to 15, or -17 (`OOM_DISABLE`) to disable OOM-killing for this
+    /// process.  This value is scaled from [oom_score_adj](Process::oom_score_adj), which should
+    /// be preferred.
+    ///
+    /// (deprecated since Linux 2.6.36)
+    pub fn oom_adj(&self) -> ProcResult<i8> {
+        let mut file = FileWrapper::open_at(&self.root, &self.fd, "oom_adj")?;
+        let mut oom = String::new();
+        file.read_to_string(&mut oom)?;
+        Ok(expect!(oom.trim().parse::<i8>()))
+    }
+
+    /// Sets the legacy OOM-killer adjustment value of this process
+    ///
+    /// The value must be in the range `-17..=15`.  The kernel converts it to an `oom_score_adj`
+    /// value (`oom_adj * 1000 / 17`), so writing back the value read by [oom_adj()](Self::oom_adj)
+    /// can lower the `oom_score_adj` of the process.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcError::Other` if the value is out of range, and
+    /// `ProcError::PermissionDenied` if the caller tries to lower the value without the
+    /// `CAP_SYS_RESOURCE` capability.
+    ///
+    /// (deprecated since Linux 2.6.36)
+    pub fn set_oom_adj(&self, oom_adj: i8) -> ProcResult<()> {
+        if !(-17..=15).contains(&oom_adj) {
+            return Err(ProcError::Other(format!(
+                "oom_adj value {} is outside the -17..=15 range",
+                oom_adj
+            )));
+        }
+        write_value(self.root.join("oom_adj"), oom_adj)
+    }
+
+    /// Returns the POSIX timers of this process, from the `/proc/[pid]/timers` file.
+    ///
+    /// (since Linux 3.10 and requires CONFIG_CHECKPOINT_RESTORE)
+    pub fn timers(&self) -> ProcResult<Timers> {
+        self.read("timers")
+    }
+
+    /// Returns the "current" timer slack value of this process, in nanoseconds
+    ///
+    /// The timer slack is used by the kernel to group timer expirations for this process that
+    /// are close to one another.  See `prctl(2)` for details.
+    ///
+    /// (since Linux 4.6)
+    pub fn timerslack_ns(&self) -> ProcResult<u64> {
+        let mut file = FileWrapper::open_at(&self.root, &self.fd, "timerslack_ns")?;
+        let mut s = String::new();
+        file.read_to_string(&mut s)?;
+        Ok(expect!(s.trim().parse::<u64>()))
+    }
+
+    /// Sets the "current" timer slack value of this process, in nanoseconds
+    ///
+    /// Setting the value to 0 resets the timer slack to the process's default value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcError::PermissionDenied` if the caller tries to change the timer slack of
+    /// another process without the `CAP_SYS_NICE` capability, or if the caller isn't allowed to
+    /// `ptrace(2)` the process.
+    ///
+    /// (since Linux 4.6)
+    pub fn set_timerslack_ns(&self, timerslack_ns: u64) -> ProcResult<()> {
+        write_value(self.root.join("timerslack_ns"), timerslack_ns)
+    }
+
     /// Set process memory information
     ///
     /// Much of this data is the same as the data from `stat()` and `status()`
@@ -643,6 +860,16 @@ impl Process {
         Task::from_process_at(&self.root, self.fd.as_fd(), path, self.pid, tid)
     }
 
+    /// Returns scheduler debugging information about the main thread of this process, from the
+    /// `/proc/<pid>/sched` file.
+    ///
+    /// See also [Task::sched()].
+    ///
+    /// (requires CONFIG_SCHED_DEBUG)
+    pub fn sched(&self) -> ProcResult<Sched> {
+        self.read("sched")
+    }
+
     /// Return the `Schedstat` for this process, based on the `/proc/<pid>/schedstat` file.
     ///
     /// (Requires CONFIG_SCHED_INFO)
@@ -812,6 +1039,20 @@ impl Process {
         self.read("net/unix").map(|net::UnixNetEntries(e)| e)
     }
 
+    /// Returns wireless statistics for all wireless interfaces in the process net namespace
+    ///
+    /// See also the [wireless()](crate::net::wireless()) function.
+    pub fn wireless(&self) -> ProcResult<Vec<net::WirelessEntry>> {
+        self.read("net/wireless").map(|net::WirelessEntries(e)| e)
+    }
+
+    /// Returns the IPv6 addresses of all interfaces in the process net namespace
+    ///
+    /// See also the [if_inet6()](crate::net::if_inet6()) function.
+    pub fn if_inet6(&self) -> ProcResult<Vec<net::Inet6AddrEntry>> {
+        self.read("net/if_inet6").map(|net::Inet6AddrEntries(e)| e)
+    }
+
     /// Reads the ARP table from the process net namespace
     pub fn arp(&self) -> ProcResult<Vec<net::ARPEntry>> {
         self.read("net/arp").map(|net::ArpEntries(e)| e)
@@ -1,3 +1,4 @@
+use super::{MMapPath, MemoryMap};
 use crate::{FileWrapper, ProcResult};
 use procfs_core::process::PageInfo;
 use std::{
@@ -6,6 +7,9 @@ use std::{
     ops::{Bound, RangeBounds},
 };
 
+/// The number of entries read at once by [PageMap::for_each_in_map()]
+const MAP_BATCH: usize = 64 * 1024;
+
 impl super::Process {
     /// Returns a struct that can be used to access information in the `/proc/pid/pagemap` file.
     pub fn pagemap(&self) -> ProcResult<PageMap> {
@@ -63,4 +67,32 @@ impl PageMap {
 
         Ok(page_infos)
     }
+
+    /// Reads the page table entries for all pages of a mapping, in batches
+    ///
+    /// `f` is called with the index of the first page of each batch and the entries of the batch,
+    /// so that large mappings can be read without large allocations.  Nothing is read for the
+    /// `[vsyscall]` mapping, which is outside of the user address space and can't be read from
+    /// pagemap.
+    pub fn for_each_in_map<F>(&mut self, map: &MemoryMap, mut f: F) -> ProcResult<()>
+    where
+        F: FnMut(usize, &[PageInfo]),
+    {
+        if map.pathname == MMapPath::Vsyscall {
+            return Ok(());
+        }
+
+        let page_size = crate::page_size();
+        let (start, end) = (
+            (map.address.0 / page_size) as usize,
+            (map.address.1 / page_size) as usize,
+        );
+        let mut index = start;
+        while index < end {
+            let batch_end = end.min(index + MAP_BATCH);
+            f(index, &self.get_range_info(index..batch_end)?);
+            index = batch_end;
+        }
+        Ok(())
+    }
 }
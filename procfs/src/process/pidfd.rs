@@ -0,0 +1,112 @@
+use super::{FDDetailsKind, Process, ProcessIdentity};
+use crate::{ProcError, ProcResult};
+use rustix::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use rustix::process::{Pid, PidfdFlags};
+
+impl Process {
+    /// Returns a `Process` for the process referred to by a pidfd
+    ///
+    /// The pidfd is kept inside the returned `Process`, see [Process::pidfd()].  Because the PID
+    /// is checked again after `/proc/<pid>` is opened, the returned `Process` is guaranteed to
+    /// refer to the same process as the pidfd, even if the PID is reused.
+    ///
+    /// Returns `ProcError::NotFound` if the process has already exited.
+    ///
+    /// (since Linux 5.4)
+    pub fn from_pidfd(pidfd: OwnedFd) -> ProcResult<Process> {
+        let pid = pidfd_pid(pidfd.as_fd())?;
+        let mut process = Process::new(pid)?;
+        // if the process exited before /proc/<pid> was opened, the PID may have been reused
+        if pidfd_pid(pidfd.as_fd())? != pid {
+            return Err(ProcError::NotFound(Some(process.root)));
+        }
+        process.pidfd = Some(pidfd);
+        Ok(process)
+    }
+
+    /// Returns a `Process` for the process with the given identity
+    ///
+    /// Returns `ProcError::NotFound` if that process has exited, even if its PID now belongs
+    /// to some other process.
+    pub fn from_identity(identity: &ProcessIdentity) -> ProcResult<Process> {
+        let process = Process::new(identity.pid)?;
+        if process.is_same_process(identity)? {
+            Ok(process)
+        } else {
+            Err(ProcError::NotFound(Some(process.root)))
+        }
+    }
+
+    /// Returns the pidfd of this process, if one has been opened
+    ///
+    /// A pidfd can be used to send signals to (or wait for) a process without the risk of the PID
+    /// being reused by another process in the meantime.  See `pidfd_open(2)` for details.
+    ///
+    /// See also [Process::open_pidfd()] and [Process::from_pidfd()].
+    pub fn pidfd(&self) -> Option<BorrowedFd<'_>> {
+        self.pidfd.as_ref().map(|fd| fd.as_fd())
+    }
+
+    /// Opens a pidfd for this process (if one isn't already open), and returns it
+    ///
+    /// The pidfd is guaranteed to refer to the same process as this `Process`, even if the process
+    /// has exited and its PID has been reused since this `Process` was created, in which case
+    /// `ProcError::NotFound` is returned.
+    ///
+    /// The PID is interpreted in the PID namespace of the caller, so this should only be used on
+    /// processes from the `/proc` filesystem of the caller's PID namespace.
+    ///
+    /// (since Linux 5.3)
+    pub fn open_pidfd(&mut self) -> ProcResult<BorrowedFd<'_>> {
+        let pidfd = match self.pidfd.take() {
+            Some(pidfd) => pidfd,
+            None => {
+                let pid = Pid::from_raw(self.pid).ok_or_else(|| ProcError::NotFound(Some(self.root.clone())))?;
+                let pidfd = wrap_io_error!(self.root, rustix::process::pidfd_open(pid, PidfdFlags::empty()))?;
+                // If the process behind our /proc/<pid> fd is still around, then the PID hasn't
+                // been reused and so the pidfd refers to the same process
+                self.stat()?;
+                pidfd
+            }
+        };
+        let pidfd: &OwnedFd = self.pidfd.insert(pidfd);
+        Ok(pidfd.as_fd())
+    }
+
+    /// Returns a key that identifies this process, even after its PID has been reused
+    ///
+    /// This is the same as `self.stat()?.identity()`.
+    pub fn identity(&self) -> ProcResult<ProcessIdentity> {
+        Ok(self.stat()?.identity())
+    }
+
+    /// Checks whether this process is the (still running) process with the given identity
+    ///
+    /// Returns `Ok(false)` if the identity belongs to a different process, or if this process has
+    /// exited.  This can be used by long-lived monitors to detect that a PID they have seen
+    /// before now belongs to a different process.
+    pub fn is_same_process(&self, identity: &ProcessIdentity) -> ProcResult<bool> {
+        if self.pid != identity.pid {
+            return Ok(false);
+        }
+        match self.stat() {
+            Ok(stat) => Ok(stat.starttime == identity.starttime),
+            Err(ProcError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Returns the PID that a pidfd refers to, from `/proc/self/fdinfo/<fd>`
+fn pidfd_pid(pidfd: BorrowedFd) -> ProcResult<i32> {
+    let details = Process::myself()?.fdinfo(pidfd.as_raw_fd())?;
+    match details.kind {
+        FDDetailsKind::PidFd { pid, .. } if pid > 0 => Ok(pid),
+        // the process has exited
+        FDDetailsKind::PidFd { pid: -1, .. } => Err(ProcError::NotFound(None)),
+        FDDetailsKind::PidFd { .. } => Err(ProcError::Other(
+            "The process of the pidfd is not in the PID namespace of the caller".to_string(),
+        )),
+        _ => Err(ProcError::Other("The file descriptor is not a pidfd".to_string())),
+    }
+}
@@ -0,0 +1,198 @@
+use super::{MemoryMap, MemoryPageFlags, PageInfo, Pfn, Process};
+use crate::{KPageFlags, PhysicalPageFlags, ProcError, ProcResult};
+use std::collections::BTreeMap;
+
+/// PFNs that are at most this many pages apart are read from `/proc/kpageflags` at once
+const KPAGEFLAGS_MAX_GAP: u64 = 512;
+
+/// Page counts for one or more mappings, from `/proc/<pid>/pagemap`
+///
+/// All values are in pages; see [ResidencyReport::page_size].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResidencyCounts {
+    /// The number of pages in the virtual address range
+    pub pages: u64,
+    /// Pages that are present in RAM
+    pub resident: u64,
+    /// Pages that are swapped out
+    pub swapped: u64,
+    /// Swapped pages, keyed by swap type (the index of the swap area, in the order of `/proc/swaps`)
+    pub swapped_by_type: BTreeMap<u64, u64>,
+    /// Resident pages that are file pages or shared anonymous pages
+    pub file: u64,
+    /// Resident pages that are private anonymous pages
+    pub anonymous: u64,
+    /// Resident pages that are mapped only by this process
+    ///
+    /// (since Linux 4.2)
+    pub exclusive: u64,
+    /// Resident pages that are part of a transparent huge page
+    ///
+    /// This is `None` if `/proc/kpageflags` can't be read (it requires CAP_SYS_ADMIN).
+    pub thp: Option<u64>,
+    /// Resident pages that are shared by kernel samepage merging
+    ///
+    /// This is `None` if `/proc/kpageflags` can't be read (it requires CAP_SYS_ADMIN).
+    pub ksm: Option<u64>,
+}
+
+impl ResidencyCounts {
+    fn add(&mut self, other: &ResidencyCounts) {
+        self.pages += other.pages;
+        self.resident += other.resident;
+        self.swapped += other.swapped;
+        for (swap_type, count) in &other.swapped_by_type {
+            *self.swapped_by_type.entry(*swap_type).or_default() += count;
+        }
+        self.file += other.file;
+        self.anonymous += other.anonymous;
+        self.exclusive += other.exclusive;
+        self.thp = self.thp.zip(other.thp).map(|(a, b)| a + b);
+        self.ksm = self.ksm.zip(other.ksm).map(|(a, b)| a + b);
+    }
+}
+
+/// The page counts of one mapping
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct MappingResidency {
+    /// The mapping
+    pub map: MemoryMap,
+    /// The page counts of the mapping
+    pub counts: ResidencyCounts,
+}
+
+/// The residency of every mapping of a process
+///
+/// Created by [Process::residency()].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ResidencyReport {
+    /// The size of a page, in bytes
+    pub page_size: u64,
+    /// Every mapping of the process
+    pub mappings: Vec<MappingResidency>,
+    /// The sum of all mappings
+    pub total: ResidencyCounts,
+}
+
+impl Process {
+    /// Reports how many pages of each mapping are resident, swapped, file-backed or anonymous
+    ///
+    /// This walks every mapping from [maps()](Process::maps) and reads the matching entries of
+    /// `/proc/<pid>/pagemap` in chunks, so that large address spaces can be scanned without large
+    /// allocations.  If `/proc/kpageflags` can be read, the resident pages are also looked up there (in
+    /// sorted batches) to count transparent huge pages and KSM pages.
+    ///
+    /// Reading the pagemap of another process requires PTRACE_MODE_READ access to it.
+    /// Since Linux 4.2, page frame numbers are only reported to callers with CAP_SYS_ADMIN, so THP
+    /// and KSM pages are only counted when running as root.
+    ///
+    /// (requires a kernel built with `CONFIG_PROC_PAGE_MONITOR`)
+    pub fn residency(&self) -> ProcResult<ResidencyReport> {
+        let page_size = crate::page_size();
+        let mut pagemap = self.pagemap()?;
+        let mut kpageflags = match KPageFlags::new() {
+            Ok(kpageflags) => Some(kpageflags),
+            Err(ProcError::PermissionDenied(_)) | Err(ProcError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        let mut total = ResidencyCounts::default();
+        if kpageflags.is_some() {
+            total.thp = Some(0);
+            total.ksm = Some(0);
+        }
+
+        let mut mappings = Vec::new();
+        let mut pfns = Vec::new();
+        for map in self.maps()? {
+            let mut counts = ResidencyCounts {
+                pages: (map.address.1 - map.address.0) / page_size,
+                ..Default::default()
+            };
+            if kpageflags.is_some() {
+                counts.thp = Some(0);
+                counts.ksm = Some(0);
+            }
+
+            pagemap.for_each_in_map(&map, |_, batch| {
+                pfns.clear();
+                for info in batch {
+                    count_page(&mut counts, info, &mut pfns);
+                }
+                if let Some(kpageflags) = kpageflags.as_mut() {
+                    count_physical_pages(&mut counts, &mut pfns, kpageflags);
+                }
+            })?;
+
+            total.add(&counts);
+            mappings.push(MappingResidency { map, counts });
+        }
+
+        Ok(ResidencyReport {
+            page_size,
+            mappings,
+            total,
+        })
+    }
+}
+
+/// Counts a page from pagemap, and collects the PFN of a resident page in `pfns`
+fn count_page(counts: &mut ResidencyCounts, info: &PageInfo, pfns: &mut Vec<u64>) {
+    match info {
+        PageInfo::MemoryPage(flags) if flags.contains(MemoryPageFlags::PRESENT) => {
+            counts.resident += 1;
+            if flags.contains(MemoryPageFlags::FILE) {
+                counts.file += 1;
+            } else {
+                counts.anonymous += 1;
+            }
+            if flags.contains(MemoryPageFlags::MMAP_EXCLUSIVE) {
+                counts.exclusive += 1;
+            }
+            pfns.push(flags.get_page_frame_number().0);
+        }
+        PageInfo::MemoryPage(_) => {}
+        PageInfo::SwapPage(flags) => {
+            counts.swapped += 1;
+            *counts.swapped_by_type.entry(flags.get_swap_type()).or_default() += 1;
+        }
+    }
+}
+
+/// Counts the THP and KSM pages among `pfns`, reading `/proc/kpageflags` in sorted ranges
+fn count_physical_pages(counts: &mut ResidencyCounts, pfns: &mut [u64], kpageflags: &mut KPageFlags) {
+    let mut count = |page: PhysicalPageFlags| {
+        if page.contains(PhysicalPageFlags::THP) {
+            counts.thp = counts.thp.map(|n| n + 1);
+        }
+        if page.contains(PhysicalPageFlags::KSM) {
+            counts.ksm = counts.ksm.map(|n| n + 1);
+        }
+    };
+
+    pfns.sort_unstable();
+    let mut rest = &pfns[..];
+    while let Some(&first) = rest.first() {
+        // read up to the next large gap at once
+        let len = rest
+            .windows(2)
+            .position(|pair| pair[1] - pair[0] > KPAGEFLAGS_MAX_GAP)
+            .map_or(rest.len(), |i| i + 1);
+        let (range, next) = rest.split_at(len);
+        match kpageflags.get_range_info(Pfn(first), Pfn(range[len - 1] + 1)) {
+            Ok(pages) => range.iter().for_each(|pfn| count(pages[(pfn - first) as usize])),
+            // pages outside of RAM (such as device memory) aren't in kpageflags, so look them up one by one
+            Err(_) => {
+                for pfn in range {
+                    if let Ok(page) = kpageflags.get_info(Pfn(*pfn)) {
+                        count(page);
+                    }
+                }
+            }
+        }
+        rest = next;
+    }
+}
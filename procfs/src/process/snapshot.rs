@@ -0,0 +1,271 @@
+use super::{Io, Limits, Process, ProcessesIter, Schedstat, Stat, StatM, Status};
+use crate::{ProcError, ProcResult, ProcessCGroups};
+use bitflags::bitflags;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+
+bitflags! {
+    /// The data to collect in a [ProcessSnapshot]
+    #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+    pub struct SnapshotFields: u32 {
+        /// [Process::stat()]
+        const STAT = 1 << 0;
+        /// [Process::statm()]
+        const STATM = 1 << 1;
+        /// [Process::status()]
+        const STATUS = 1 << 2;
+        /// [Process::io()]
+        const IO = 1 << 3;
+        /// [Process::cmdline()]
+        const CMDLINE = 1 << 4;
+        /// [Process::environ()]
+        const ENVIRON = 1 << 5;
+        /// [Process::exe()]
+        const EXE = 1 << 6;
+        /// [Process::cwd()]
+        const CWD = 1 << 7;
+        /// [Process::limits()]
+        const LIMITS = 1 << 8;
+        /// [Process::cgroups()]
+        const CGROUPS = 1 << 9;
+        /// [Process::schedstat()]
+        const SCHEDSTAT = 1 << 10;
+        /// [Process::oom_score()]
+        const OOM_SCORE = 1 << 11;
+        /// [Process::fd_count()]
+        const FD_COUNT = 1 << 12;
+    }
+}
+
+/// The reason that a field of a [ProcessSnapshot] couldn't be read
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FieldError {
+    /// The process exited (or became a zombie) before this field was read
+    Exited,
+    /// The caller doesn't have permission to read this field
+    PermissionDenied,
+    /// The file for this field doesn't exist, even though the process is still running
+    ///
+    /// This usually means that the kernel is too old, or was built without the required
+    /// configuration option.
+    Unavailable,
+    /// Some other error
+    Other(ProcError),
+}
+
+/// The result of reading one field of a [ProcessSnapshot]
+///
+/// This is `None` if the field was not requested.
+pub type SnapshotField<T> = Option<Result<T, FieldError>>;
+
+/// Data about a process, collected from several files in `/proc/<pid>/`
+///
+/// Created by [Process::snapshot()] or [ProcessesIter::snapshots()].
+///
+/// All fields are read through the same `/proc/<pid>` directory handle, so they all describe the
+/// same process even if its PID is reused while the snapshot is taken.  Reading the files is not
+/// atomic, though, so the data may come from slightly different points in time.
+///
+/// Errors are recorded per field, so one unreadable file doesn't prevent the other fields from
+/// being collected.  Once the process is found to have exited, the remaining fields are not read
+/// and are set to [`FieldError::Exited`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ProcessSnapshot {
+    /// The process ID
+    pub pid: i32,
+    /// The fields that were requested
+    pub fields: SnapshotFields,
+    /// True if the process exited (or became a zombie) while the snapshot was taken
+    pub exited: bool,
+    /// [Process::stat()], if requested
+    pub stat: SnapshotField<Stat>,
+    /// [Process::statm()], if requested
+    pub statm: SnapshotField<StatM>,
+    /// [Process::status()], if requested
+    pub status: SnapshotField<Status>,
+    /// [Process::io()], if requested
+    pub io: SnapshotField<Io>,
+    /// [Process::cmdline()], if requested
+    pub cmdline: SnapshotField<Vec<String>>,
+    /// [Process::environ()], if requested
+    pub environ: SnapshotField<HashMap<OsString, OsString>>,
+    /// [Process::exe()], if requested
+    pub exe: SnapshotField<PathBuf>,
+    /// [Process::cwd()], if requested
+    pub cwd: SnapshotField<PathBuf>,
+    /// [Process::limits()], if requested
+    pub limits: SnapshotField<Limits>,
+    /// [Process::cgroups()], if requested
+    pub cgroups: SnapshotField<ProcessCGroups>,
+    /// [Process::schedstat()], if requested
+    pub schedstat: SnapshotField<Schedstat>,
+    /// [Process::oom_score()], if requested
+    pub oom_score: SnapshotField<u16>,
+    /// [Process::fd_count()], if requested
+    pub fd_count: SnapshotField<usize>,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldError::Exited => write!(f, "The process exited before the field was read"),
+            FieldError::PermissionDenied => write!(f, "Permission denied"),
+            FieldError::Unavailable => write!(f, "The field is not available on this system"),
+            FieldError::Other(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl ProcessSnapshot {
+    /// Returns the requested fields that could not be read, along with their errors
+    pub fn errors(&self) -> Vec<(SnapshotFields, &FieldError)> {
+        fn err<T>(field: SnapshotFields, value: &SnapshotField<T>) -> Option<(SnapshotFields, &FieldError)> {
+            match value {
+                Some(Err(e)) => Some((field, e)),
+                _ => None,
+            }
+        }
+
+        vec![
+            err(SnapshotFields::STAT, &self.stat),
+            err(SnapshotFields::STATM, &self.statm),
+            err(SnapshotFields::STATUS, &self.status),
+            err(SnapshotFields::IO, &self.io),
+            err(SnapshotFields::CMDLINE, &self.cmdline),
+            err(SnapshotFields::ENVIRON, &self.environ),
+            err(SnapshotFields::EXE, &self.exe),
+            err(SnapshotFields::CWD, &self.cwd),
+            err(SnapshotFields::LIMITS, &self.limits),
+            err(SnapshotFields::CGROUPS, &self.cgroups),
+            err(SnapshotFields::SCHEDSTAT, &self.schedstat),
+            err(SnapshotFields::OOM_SCORE, &self.oom_score),
+            err(SnapshotFields::FD_COUNT, &self.fd_count),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Returns true if all of the requested fields were read successfully
+    pub fn is_complete(&self) -> bool {
+        self.errors().is_empty()
+    }
+}
+
+/// Reads the fields of a snapshot, keeping track of whether the process has exited
+struct SnapshotReader<'a> {
+    process: &'a Process,
+    fields: SnapshotFields,
+    exited: bool,
+}
+
+impl SnapshotReader<'_> {
+    fn read<T, F>(&mut self, field: SnapshotFields, f: F) -> SnapshotField<T>
+    where
+        F: FnOnce(&Process) -> ProcResult<T>,
+    {
+        if !self.fields.contains(field) {
+            return None;
+        }
+        if self.exited {
+            return Some(Err(FieldError::Exited));
+        }
+        Some(match f(self.process) {
+            Ok(value) => Ok(value),
+            Err(ProcError::PermissionDenied(_)) => Err(FieldError::PermissionDenied),
+            // reading from the directory of an exited process also returns NotFound
+            Err(ProcError::NotFound(_)) if !self.process.is_alive() => {
+                self.exited = true;
+                Err(FieldError::Exited)
+            }
+            Err(ProcError::NotFound(_)) => Err(FieldError::Unavailable),
+            Err(e) => Err(FieldError::Other(e)),
+        })
+    }
+}
+
+impl Process {
+    /// Collects the given fields of this process in a single [ProcessSnapshot]
+    ///
+    /// This never fails as a whole, errors are recorded per field instead.
+    pub fn snapshot(&self, fields: SnapshotFields) -> ProcessSnapshot {
+        let mut r = SnapshotReader {
+            process: self,
+            fields,
+            exited: false,
+        };
+        let stat = r.read(SnapshotFields::STAT, Process::stat);
+        let statm = r.read(SnapshotFields::STATM, Process::statm);
+        let status = r.read(SnapshotFields::STATUS, Process::status);
+        let io = r.read(SnapshotFields::IO, Process::io);
+        let cmdline = r.read(SnapshotFields::CMDLINE, Process::cmdline);
+        let environ = r.read(SnapshotFields::ENVIRON, Process::environ);
+        let exe = r.read(SnapshotFields::EXE, Process::exe);
+        let cwd = r.read(SnapshotFields::CWD, Process::cwd);
+        let limits = r.read(SnapshotFields::LIMITS, Process::limits);
+        let cgroups = r.read(SnapshotFields::CGROUPS, Process::cgroups);
+        let schedstat = r.read(SnapshotFields::SCHEDSTAT, Process::schedstat);
+        let oom_score = r.read(SnapshotFields::OOM_SCORE, Process::oom_score);
+        let fd_count = r.read(SnapshotFields::FD_COUNT, Process::fd_count);
+
+        ProcessSnapshot {
+            pid: self.pid,
+            fields,
+            exited: r.exited,
+            stat,
+            statm,
+            status,
+            io,
+            cmdline,
+            environ,
+            exe,
+            cwd,
+            limits,
+            cgroups,
+            schedstat,
+            oom_score,
+            fd_count,
+        }
+    }
+}
+
+impl ProcessesIter {
+    /// Turns this iterator into an iterator of [ProcessSnapshot]s with the given fields
+    ///
+    /// Processes that exit before they can be opened are skipped.  Each `Process` is dropped as
+    /// soon as its snapshot is taken, so this doesn't hold a file descriptor for every process.
+    pub fn snapshots(self, fields: SnapshotFields) -> SnapshotsIter {
+        SnapshotsIter { inner: self, fields }
+    }
+}
+
+/// An iterator over snapshots of all processes in the system
+///
+/// Created by [ProcessesIter::snapshots()].
+#[derive(Debug)]
+pub struct SnapshotsIter {
+    inner: ProcessesIter,
+    fields: SnapshotFields,
+}
+
+impl std::iter::Iterator for SnapshotsIter {
+    type Item = ProcResult<ProcessSnapshot>;
+    fn next(&mut self) -> Option<ProcResult<ProcessSnapshot>> {
+        loop {
+            match self.inner.next()? {
+                Ok(process) => return Some(Ok(process.snapshot(self.fields))),
+                // the process exited before it could be opened
+                Err(ProcError::NotFound(_)) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
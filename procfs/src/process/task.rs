@@ -1,8 +1,8 @@
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use super::{FileWrapper, Io, Schedstat, Stat, Status, Syscall};
-use crate::{ProcError, ProcResult};
+use super::{FileWrapper, Io, KernelStack, Sched, Schedstat, Stat, Status, Syscall};
+use crate::{CpuSet, ProcError, ProcResult};
 use procfs_core::FromRead;
 use rustix::fd::{BorrowedFd, OwnedFd};
 
@@ -63,6 +63,37 @@ impl Task {
         self.read("status")
     }
 
+    /// Returns the set of CPUs on which this thread may run
+    ///
+    /// This is taken from the `/proc/<pid>/task/<tid>/status` file.
+    ///
+    /// (since Linux 2.6.24)
+    pub fn cpu_affinity(&self) -> ProcResult<CpuSet> {
+        match self.status()?.cpu_affinity() {
+            Some(set) => Ok(set),
+            None => Err(ProcError::Incomplete(Some(self.root.join("status")))),
+        }
+    }
+
+    /// Sets the command name of this thread, by writing to `/proc/<pid>/task/<tid>/comm`
+    ///
+    /// The kernel silently truncates names that are longer than 15 bytes.  A thread can only
+    /// change the name of threads in its own thread group.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcError::Other` if `comm` contains a nul byte, and `ProcError::Io` with
+    /// [`ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput) if the thread is not in
+    /// the same thread group as the caller.
+    ///
+    /// (since Linux 2.6.33)
+    pub fn set_comm(&self, comm: &str) -> ProcResult<()> {
+        if comm.contains('\0') {
+            return Err(ProcError::Other(format!("comm {:?} contains a nul byte", comm)));
+        }
+        crate::write_value(self.root.join("comm"), comm)
+    }
+
     /// Thread IO info from `/proc/<pid>/task/<tid>/io`
     ///
     /// This data will be unique per task.
@@ -77,11 +108,26 @@ impl Task {
         self.read("schedstat")
     }
 
+    /// Thread scheduler debugging info from `/proc/<pid>/task/<tid>/sched`
+    ///
+    /// This data will be unique per task.
+    pub fn sched(&self) -> ProcResult<Sched> {
+        self.read("sched")
+    }
+
     /// Returns the status info from `/proc/<pid>/task/<tid>/syscall`.
     pub fn syscall(&self) -> ProcResult<Syscall> {
         self.read("syscall")
     }
 
+    /// Thread kernel stack from `/proc/<pid>/task/<tid>/stack`
+    ///
+    /// This requires the `CAP_SYS_ADMIN` capability, and will return `ProcError::PermissionDenied`
+    /// otherwise.
+    pub fn stack(&self) -> ProcResult<KernelStack> {
+        self.read("stack")
+    }
+
     /// Thread children from `/proc/<pid>/task/<tid>/children`
     ///
     /// WARNING:
@@ -172,6 +172,7 @@ fn test_all() {
         check_unwrap(&prc, prc.auxv());
         check_unwrap(&prc, prc.cgroups());
         check_unwrap(&prc, prc.wchan());
+        check_unwrap(&prc, prc.stack());
         check_unwrap(&prc, prc.status());
         check_unwrap(&prc, prc.mountinfo());
         check_unwrap(&prc, prc.mountstats());
@@ -188,6 +189,7 @@ fn test_all() {
                 check_unwrap_task(&prc, task.status());
                 check_unwrap_task(&prc, task.io());
                 check_unwrap_task(&prc, task.schedstat());
+                check_unwrap_task(&prc, task.sched());
             }
         }
     }
@@ -323,6 +325,28 @@ fn test_proc_pagemap() {
     }
 }
 
+#[test]
+fn test_proc_pagemap_for_each_in_map() {
+    let myself = Process::myself().unwrap();
+    let mut pagemap = myself.pagemap().unwrap();
+    let page_size = crate::page_size();
+
+    for map in myself.maps().unwrap() {
+        let mut next = map.address.0 / page_size;
+        pagemap
+            .for_each_in_map(&map, |start, batch| {
+                assert_eq!(start as u64, next);
+                next += batch.len() as u64;
+            })
+            .unwrap();
+        if map.pathname == MMapPath::Vsyscall {
+            assert_eq!(next, map.address.0 / page_size);
+        } else {
+            assert_eq!(next, map.address.1 / page_size);
+        }
+    }
+}
+
 #[test]
 fn test_mmap_path() {
     assert_eq!(MMapPath::from("[stack]").unwrap(), MMapPath::Stack);
@@ -465,6 +489,33 @@ fn test_proc_auxv() {
     }
 }
 
+#[test]
+fn test_auxiliary_vector() {
+    let myself = Process::myself().unwrap();
+    let auxv = myself.auxiliary_vector().unwrap();
+    assert_eq!(auxv.page_size(), Some(crate::page_size()));
+    assert_eq!(auxv.clock_ticks(), Some(crate::ticks_per_second()));
+    assert_eq!(auxv.secure(), Some(false));
+    assert!(auxv.entry().is_some_and(|entry| entry > 0));
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    assert!(auxv.x86_hwcap().unwrap().contains(X86HwCap::FPU));
+    assert_eq!(
+        myself.auxv().unwrap(),
+        auxv.entries.iter().copied().collect::<HashMap<_, _>>()
+    );
+
+    let memory = myself.memory().unwrap();
+    let strings = memory.auxv_strings(&auxv).unwrap();
+    if auxv.platform().is_some() {
+        assert!(!strings.platform.unwrap().is_empty());
+    }
+    let execfn = strings.execfn.unwrap();
+    assert_eq!(
+        execfn.file_name(),
+        std::env::args_os().next().map(PathBuf::from).unwrap().file_name()
+    );
+}
+
 #[test]
 fn test_proc_wchan() {
     let myself = Process::myself().unwrap();
@@ -472,6 +523,524 @@ fn test_proc_wchan() {
     println!("{:?}", wchan);
 }
 
+#[test]
+fn test_proc_stack() {
+    let myself = Process::myself().unwrap();
+    match myself.stack() {
+        Ok(stack) => {
+            println!("{:?}", stack);
+            assert!(!stack.is_empty());
+        }
+        Err(ProcError::PermissionDenied(_)) => assert!(!rustix::process::geteuid().is_root()),
+        // the kernel may not have been built with CONFIG_STACKTRACE
+        Err(ProcError::NotFound(_)) => {}
+        Err(e) => panic!("{:?}", e),
+    }
+
+    for task in myself.tasks().unwrap() {
+        let task = task.unwrap();
+        check_unwrap_task(&myself, task.stack());
+    }
+}
+
+#[test]
+fn test_proc_timers() {
+    let myself = Process::myself().unwrap();
+
+    let mut timer_id: libc::timer_t = std::ptr::null_mut();
+    let mut sev: libc::sigevent = unsafe { std::mem::zeroed() };
+    sev.sigev_notify = libc::SIGEV_NONE;
+    assert_eq!(
+        unsafe { libc::timer_create(libc::CLOCK_MONOTONIC, &mut sev, &mut timer_id) },
+        0
+    );
+
+    match myself.timers() {
+        Ok(timers) => {
+            println!("{:?}", timers);
+            let timer = timers.iter().find(|t| t.id as usize == timer_id as usize).unwrap();
+            assert_eq!(timer.notify, TimerNotify::None);
+            assert_eq!(timer.clock, ClockId::Monotonic);
+        }
+        // the kernel may not have been built with CONFIG_CHECKPOINT_RESTORE
+        Err(ProcError::NotFound(_)) => {}
+        Err(e) => panic!("{:?}", e),
+    }
+
+    unsafe { libc::timer_delete(timer_id) };
+}
+
+#[test]
+fn test_proc_timerslack_ns() {
+    let myself = Process::myself().unwrap();
+    let slack = myself.timerslack_ns().unwrap();
+    println!("{}", slack);
+    myself.set_timerslack_ns(slack).unwrap();
+}
+
+#[test]
+fn test_proc_setters() {
+    let myself = Process::myself().unwrap();
+
+    let tid = rustix::thread::gettid().as_raw_nonzero().get();
+    let task = myself.task_from_tid(tid).unwrap();
+    let comm = task.stat().unwrap().comm;
+    task.set_comm("procfs-setter").unwrap();
+    assert_eq!(task.stat().unwrap().comm, "procfs-setter");
+    assert!(matches!(task.set_comm("a\0b"), Err(ProcError::Other(_))));
+    // this thread belongs to the test harness, so give it its name back
+    task.set_comm(&comm).unwrap();
+    assert_eq!(task.stat().unwrap().comm, comm);
+
+    // only threads in the same thread group can be renamed
+    let mut child = std::process::Command::new("sleep").arg("10").spawn().unwrap();
+    let child_proc = Process::new(child.id() as i32).unwrap();
+    match child_proc.set_comm("renamed") {
+        Err(ProcError::Io(e, Some(_))) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+        x => panic!("Unexpected result: {:?}", x),
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    if let Some(Some(filter)) = check_unwrap(&myself, myself.coredump_filter()) {
+        myself.set_coredump_filter(filter).unwrap();
+        assert_eq!(myself.coredump_filter().unwrap(), Some(filter));
+    }
+
+    let oom_adj = myself.oom_adj().unwrap();
+    assert!((-17..=15).contains(&oom_adj));
+    // oom_adj is converted back to a (possibly lower) oom_score_adj, which needs CAP_SYS_RESOURCE
+    check_unwrap(&myself, myself.set_oom_adj(oom_adj));
+    assert!(matches!(myself.set_oom_adj(16), Err(ProcError::Other(_))));
+
+    // requires CONFIG_SCHED_AUTOGROUP
+    if let Some(autogroup) = check_unwrap(&myself, myself.autogroup()) {
+        let nice: i8 = autogroup.trim().rsplit(' ').next().unwrap().parse().unwrap();
+        myself.set_autogroup_nice(nice).unwrap();
+    }
+    assert!(matches!(myself.set_autogroup_nice(20), Err(ProcError::Other(_))));
+}
+
+#[test]
+fn test_map_files() {
+    let myself = Process::myself().unwrap();
+    let maps = myself.maps().unwrap();
+    let exe = myself.exe().unwrap();
+
+    let map_files = match myself.map_files() {
+        Ok(iter) => iter,
+        // the kernel may not have been built with CONFIG_CHECKPOINT_RESTORE
+        Err(ProcError::NotFound(_)) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+    let mut found_exe = false;
+    for map_file in map_files {
+        let Some(map_file) = check_unwrap(&myself, map_file) else {
+            continue;
+        };
+        if map_file.target == exe {
+            found_exe = true;
+            let map = maps.iter().find(|m| m.address == map_file.address).unwrap();
+            assert!(matches!(&map.pathname, MMapPath::Path(p) if *p == exe));
+
+            if let Some(file) = check_unwrap(&myself, myself.open_mapping(map)) {
+                assert_eq!(file.metadata().unwrap().len(), std::fs::metadata(&exe).unwrap().len());
+            }
+        }
+    }
+    assert!(found_exe);
+
+    if let Some(heap) = maps.iter().find(|m| m.pathname == MMapPath::Heap) {
+        assert!(myself.open_mapping(heap).is_err());
+    }
+}
+
+#[test]
+fn test_security_context() {
+    let myself = Process::myself().unwrap();
+    let context = myself.security_context().unwrap();
+    println!("{:?}", context);
+    for attr in [
+        SecurityAttr::Current,
+        SecurityAttr::Exec,
+        SecurityAttr::FsCreate,
+        SecurityAttr::KeyCreate,
+        SecurityAttr::Prev,
+        SecurityAttr::SockCreate,
+    ] {
+        if let Some(value) = check_unwrap(&myself, myself.security_attr(attr)) {
+            println!("{:?}: {:?}", attr, value);
+        }
+    }
+}
+
+#[test]
+fn test_capabilities() {
+    let myself = Process::myself().unwrap();
+    let status = myself.status().unwrap();
+    let last_cap = crate::sys::kernel::cap_last_cap().unwrap();
+
+    // the bounding set contains every capability that the kernel supports (unless some were dropped)
+    if let Some(bounding) = status.cap_bounding() {
+        assert!(Capabilities::supported(last_cap).contains(bounding));
+    }
+    assert!(status.cap_permitted().contains(status.cap_effective()));
+    println!("{:?}", status.cap_effective());
+}
+
+#[test]
+fn test_cpu_affinity() {
+    let myself = Process::myself().unwrap();
+    let status = myself.status().unwrap();
+    let affinity = myself.cpu_affinity().unwrap();
+    assert!(!affinity.is_empty());
+    if let Some(mask) = &status.cpus_allowed {
+        assert_eq!(CpuSet::from_mask_words(mask), affinity);
+    }
+    println!("cpu affinity: {} ({})", affinity, affinity.to_mask());
+    if let Some(mems) = status.mem_affinity() {
+        println!("mem affinity: {}", mems);
+    }
+
+    for task in myself.tasks().unwrap() {
+        let task = task.unwrap();
+        assert!(!task.cpu_affinity().unwrap().is_empty());
+    }
+
+    if let Some(cpuset) = check_unwrap(&myself, myself.cpuset()) {
+        assert!(cpuset.is_absolute());
+        println!("cpuset: {}", cpuset.display());
+    }
+}
+
+#[test]
+fn test_pidfd() {
+    let mut child = std::process::Command::new("sleep").arg("10").spawn().unwrap();
+    let pid = child.id() as i32;
+
+    let mut prc = Process::new(pid).unwrap();
+    assert!(prc.pidfd().is_none());
+    prc.open_pidfd().unwrap();
+    assert!(prc.pidfd().is_some());
+
+    let identity = prc.identity().unwrap();
+    assert_eq!(identity.pid, pid);
+    assert!(prc.is_same_process(&identity).unwrap());
+    assert!(!Process::myself().unwrap().is_same_process(&identity).unwrap());
+    assert_eq!(Process::from_identity(&identity).unwrap().pid, pid);
+
+    let pidfd = rustix::process::pidfd_open(
+        rustix::process::Pid::from_raw(pid).unwrap(),
+        rustix::process::PidfdFlags::empty(),
+    )
+    .unwrap();
+    let from_pidfd = Process::from_pidfd(pidfd).unwrap();
+    assert_eq!(from_pidfd.identity().unwrap(), identity);
+
+    let other = ProcessIdentity {
+        pid,
+        starttime: identity.starttime + 1,
+    };
+    assert!(!prc.is_same_process(&other).unwrap());
+    assert!(matches!(Process::from_identity(&other), Err(ProcError::NotFound(_))));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(!prc.is_same_process(&identity).unwrap());
+    assert!(matches!(Process::from_identity(&identity), Err(ProcError::NotFound(_))));
+    let pidfd = from_pidfd.pidfd().unwrap().try_clone_to_owned().unwrap();
+    assert!(matches!(Process::from_pidfd(pidfd), Err(ProcError::NotFound(_))));
+}
+
+#[test]
+fn test_snapshot() {
+    let myself = Process::myself().unwrap();
+    let snapshot = myself.snapshot(SnapshotFields::STAT | SnapshotFields::STATUS | SnapshotFields::CMDLINE);
+    assert!(!snapshot.exited);
+    assert!(snapshot.is_complete(), "{:?}", snapshot.errors());
+    assert_eq!(snapshot.stat.unwrap().unwrap().pid, myself.pid);
+    assert!(snapshot.status.is_some());
+    assert!(snapshot.io.is_none());
+
+    // a process that exits while being snapshotted
+    let mut child = std::process::Command::new("sleep").arg("10").spawn().unwrap();
+    let prc = Process::new(child.id() as i32).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    let snapshot = prc.snapshot(SnapshotFields::all());
+    assert!(snapshot.exited);
+    assert!(matches!(snapshot.cmdline, Some(Err(FieldError::Exited))));
+    assert!(matches!(snapshot.oom_score, Some(Err(FieldError::Exited))));
+    assert_eq!(
+        FieldError::Exited.to_string(),
+        "The process exited before the field was read"
+    );
+
+    let mut count = 0;
+    for snapshot in all_processes()
+        .unwrap()
+        .snapshots(SnapshotFields::STAT | SnapshotFields::IO | SnapshotFields::EXE)
+    {
+        let snapshot = snapshot.unwrap();
+        for (field, err) in snapshot.errors() {
+            if let FieldError::Other(e) = err {
+                panic!("Unexpected error reading {:?} of {}: {:?}", field, snapshot.pid, e);
+            }
+        }
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn test_process_tree() {
+    // pid, ppid, pgrp, session, tpgid, utime, rss
+    let procs = [
+        (1, 0, 1, 1, -1, 10, 100),
+        (100, 1, 100, 100, 120, 1, 10),
+        (110, 100, 110, 100, 120, 2, 20),
+        (111, 110, 110, 100, 120, 3, 30),
+        (120, 100, 120, 100, 120, 4, 40),
+        (200, 999, 200, 200, -1, 5, 50),
+    ];
+    let stats = procs.iter().map(|&(pid, ppid, pgrp, session, tpgid, utime, rss)| {
+        let line = format!(
+            "{} (p{}) S {} {} {} 0 {} 0 0 0 0 0 {} 1 0 0 20 0 2 0 100 1000 {} 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+            pid, pid, ppid, pgrp, session, tpgid, utime, rss
+        );
+        Stat::from_read(line.as_bytes()).unwrap()
+    });
+    let tree = ProcessTree::from_stats(stats);
+    assert_eq!(tree.len(), 6);
+
+    let roots: Vec<_> = tree.roots().map(|n| n.pid()).collect();
+    assert_eq!(roots, vec![1, 200]);
+    let orphans: Vec<_> = tree.orphans().map(|n| n.pid()).collect();
+    assert_eq!(orphans, vec![200]);
+
+    let children: Vec<_> = tree.children(100).map(|n| n.pid()).collect();
+    assert_eq!(children, vec![110, 120]);
+    let ancestors: Vec<_> = tree.ancestors(111).iter().map(|n| n.pid()).collect();
+    assert_eq!(ancestors, vec![110, 100, 1]);
+    let descendants: Vec<_> = tree.descendants(100).iter().map(|n| n.pid()).collect();
+    assert_eq!(descendants, vec![110, 111, 120]);
+
+    assert_eq!(tree.session(100).len(), 4);
+    assert_eq!(tree.process_group(110).len(), 2);
+    assert!(tree.get(120).unwrap().is_foreground());
+    assert!(!tree.get(110).unwrap().is_foreground());
+    assert_eq!(tree.sessions().get(&100).unwrap().len(), 3);
+    // 100 is a session leader whose parent is in another session
+    assert!(tree.orphaned_process_groups().contains(&100));
+    assert!(!tree.orphaned_process_groups().contains(&110));
+
+    let totals = tree.subtree_totals(100).unwrap();
+    assert_eq!(totals.processes, 4);
+    assert_eq!(totals.utime, 1 + 2 + 3 + 4);
+    assert_eq!(totals.rss, 10 + 20 + 30 + 40);
+    assert_eq!(totals.threads, 8);
+    assert_eq!(totals.fd_count_unknown, 4);
+    assert!(tree.subtree_totals(5).is_none());
+
+    let tree = ProcessTree::new().unwrap();
+    let myself = Process::myself().unwrap();
+    let me = tree.get(myself.pid).unwrap();
+    assert!(me.fd_count.is_some());
+    assert!(tree.ancestors(myself.pid).iter().all(|n| n.pid() != myself.pid));
+    let totals = tree.subtree_totals(myself.pid).unwrap();
+    assert!(totals.processes >= 1);
+    assert!(totals.fd_count > 0);
+    assert!(me.cmdline.is_none());
+
+    let snapshots = vec![myself.snapshot(SnapshotFields::STAT | SnapshotFields::CMDLINE)];
+    let tree = ProcessTree::from_snapshots(snapshots);
+    assert_eq!(tree.get(myself.pid).unwrap().cmdline, Some(myself.cmdline().unwrap()));
+}
+
+#[test]
+fn test_usage_sample() {
+    let myself = Process::myself().unwrap();
+    let before = UsageSample::processes().unwrap();
+    assert!(before.entries.contains_key(&myself.pid));
+
+    // burn some cpu time so that our own rates are non-zero
+    let start = std::time::Instant::now();
+    let mut x = 0u64;
+    while start.elapsed() < std::time::Duration::from_millis(200) {
+        x = x.wrapping_add(std::hint::black_box(1));
+    }
+    assert!(x > 0);
+
+    let after = UsageSample::processes().unwrap();
+    let rates = after.rates_since(&before);
+    let me = rates.iter().find(|r| r.identity.pid == myself.pid).unwrap();
+    assert!(!me.is_new);
+    assert!(me.cpu_percent >= 0.0);
+    assert!((me.cpu_percent - (me.user_percent + me.system_percent)).abs() < 1e-6);
+    assert!(after.exited_since(&before).iter().all(|e| e.identity.pid != myself.pid));
+
+    // a reused pid is treated as a new process
+    let mut reused = before.clone();
+    reused.entries.get_mut(&myself.pid).unwrap().identity.starttime += 1;
+    let rates = after.rates_since(&reused);
+    assert!(rates.iter().find(|r| r.identity.pid == myself.pid).unwrap().is_new);
+    assert!(after.exited_since(&reused).iter().any(|e| e.identity.pid == myself.pid));
+
+    let before = UsageSample::tasks(&myself).unwrap();
+    let after = UsageSample::tasks(&myself).unwrap();
+    let rates = after.rates_since(&before);
+    assert!(rates.iter().any(|r| r.identity.pid == myself.pid));
+    assert!(rates.iter().all(|r| r.voluntary_ctxt_switches_per_sec.is_some()));
+}
+
+#[test]
+fn test_smaps_breakdown() {
+    let myself = Process::myself().unwrap();
+    let smaps = match myself.smaps() {
+        Ok(smaps) => smaps,
+        // requires CONFIG_PROC_PAGE_MONITOR
+        Err(ProcError::NotFound(_)) => return,
+        Err(e) => panic!("{}", e),
+    };
+    let breakdown = smaps.breakdown();
+    assert_eq!(breakdown.total.mappings, smaps.len());
+    assert!(breakdown.total.rss > 0);
+    assert!(breakdown.total.pss <= breakdown.total.rss);
+    assert!(breakdown.total.uss <= breakdown.total.pss);
+    assert!(breakdown.categories.contains_key(&MemoryCategory::Stack));
+    let sum: u64 = breakdown.categories.values().map(|u| u.rss).sum();
+    assert_eq!(sum, breakdown.total.rss);
+}
+
+#[test]
+fn test_working_set_tracker() {
+    let myself = Process::myself().unwrap();
+    let tracker = match myself.working_set_tracker(WorkingSetMode::SoftDirty) {
+        Ok(tracker) => tracker,
+        // requires CONFIG_PROC_PAGE_MONITOR
+        Err(ProcError::NotFound(_)) => return,
+        Err(e) => panic!("{}", e),
+    };
+
+    // write to a few pages, so that they become soft-dirty again
+    let page_size = crate::page_size() as usize;
+    let mut buf = vec![0u8; page_size * 16];
+    for i in (0..buf.len()).step_by(page_size) {
+        buf[i] = std::hint::black_box(1);
+    }
+
+    let report = tracker.measure().unwrap();
+    assert_eq!(report.pages, report.mappings.iter().map(|m| m.pages).sum::<u64>());
+    assert_eq!(report.bytes, report.pages * page_size as u64);
+    assert!(!report.mappings.is_empty());
+
+    // without CONFIG_MEM_SOFT_DIRTY, the soft-dirty bit is never set
+    let address = buf.as_ptr() as u64;
+    let page = (address / page_size as u64) as usize;
+    let soft_dirty = match myself.pagemap().unwrap().get_info(page).unwrap() {
+        PageInfo::MemoryPage(flags) => flags.contains(MemoryPageFlags::SOFT_DIRTY),
+        PageInfo::SwapPage(flags) => flags.contains(SwapPageFlags::SOFT_DIRTY),
+    };
+    if soft_dirty {
+        let mapping = report
+            .mappings
+            .iter()
+            .find(|m| m.map.address.0 <= address && address < m.map.address.1)
+            .unwrap();
+        assert!(mapping.pages >= 16, "{:?}", mapping);
+    }
+
+    let tracker = myself.working_set_tracker(WorkingSetMode::Referenced).unwrap();
+    let report = tracker.measure().unwrap();
+    assert_eq!(report.mode, WorkingSetMode::Referenced);
+    assert!(report.pages > 0);
+}
+
+#[test]
+fn test_residency() {
+    let myself = Process::myself().unwrap();
+    let report = match myself.residency() {
+        Ok(report) => report,
+        // requires CONFIG_PROC_PAGE_MONITOR
+        Err(ProcError::NotFound(_)) => return,
+        Err(e) => panic!("{}", e),
+    };
+    assert!(report.total.resident > 0);
+    assert_eq!(report.total.resident, report.total.file + report.total.anonymous);
+    assert_eq!(report.total.swapped, report.total.swapped_by_type.values().sum::<u64>());
+    assert_eq!(
+        report.total.pages,
+        report.mappings.iter().map(|m| m.counts.pages).sum::<u64>()
+    );
+    for mapping in &report.mappings {
+        let counts = &mapping.counts;
+        assert!(counts.resident + counts.swapped <= counts.pages);
+        assert!(counts.exclusive <= counts.resident);
+    }
+    assert_eq!(report.total.thp.is_some(), report.total.ksm.is_some());
+}
+
+#[test]
+fn test_process_memory() {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct Pair {
+        a: u32,
+        b: u64,
+    }
+
+    let pair = Pair {
+        a: 7,
+        b: 0x0123456789abcdef,
+    };
+    let name = std::ffi::CString::new("procfs-memory-test").unwrap();
+    let pattern = b"\x13procfs\x37pattern\x42".to_vec();
+    let page_size = crate::page_size() as usize;
+    let guard = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            page_size,
+            libc::PROT_NONE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+    assert_ne!(guard, libc::MAP_FAILED);
+
+    let myself = Process::myself().unwrap();
+    let memory = myself.memory().unwrap();
+
+    let read: Pair = unsafe { memory.read_struct(&pair as *const Pair as u64) }.unwrap();
+    assert_eq!(read, pair);
+
+    let address = name.as_ptr() as u64;
+    assert_eq!(memory.read_c_string(address, 64).unwrap(), name);
+    assert!(matches!(
+        memory.read_c_string(address, 4),
+        Err(MemoryError::NotTerminated { max: 4, .. })
+    ));
+
+    let ranges = [address..address + 6, address + 7..address + 13];
+    let bufs = memory.read_vectored(&ranges).unwrap();
+    assert_eq!(bufs, vec![b"procfs".to_vec(), b"memory".to_vec()]);
+
+    let found = memory.search(&pattern).unwrap();
+    assert!(found.contains(&(pattern.as_ptr() as u64)));
+
+    let mut buf = [0; 8];
+    assert!(matches!(
+        memory.read_at(0, &mut buf),
+        Err(MemoryError::Unmapped { address: 0 })
+    ));
+    assert!(matches!(
+        memory.read_at(guard as u64, &mut buf),
+        Err(MemoryError::NotReadable { .. })
+    ));
+
+    unsafe { libc::munmap(guard, page_size) };
+}
+
 #[test]
 fn test_proc_loginuid() {
     if !Path::new("/proc/self/loginuid").exists() {
@@ -533,6 +1102,16 @@ fn test_schedstat() {
     println!("{:#?}", schedstat);
 }
 
+#[test]
+fn test_sched() {
+    let me = Process::myself().unwrap();
+    if let Some(sched) = check_unwrap(&me, me.sched()) {
+        assert_eq!(sched.pid, me.pid);
+        assert!(sched.nr_switches >= sched.nr_voluntary_switches);
+        println!("{:#?}", sched);
+    }
+}
+
 #[test]
 fn test_fdtarget() {
     // https://github.com/eminence/procfs/issues/352
@@ -553,6 +1132,65 @@ fn test_fdtarget_memfd() {
     assert!(matches!(memfd, FDTarget::MemFD(s) if s == "test"));
 }
 
+#[test]
+fn test_numa_maps() {
+    let me = Process::myself().unwrap();
+    let numa_maps = match me.numa_maps() {
+        Ok(maps) => maps,
+        // the kernel may not have been built with CONFIG_NUMA
+        Err(ProcError::NotFound(_)) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+    let totals = numa_maps.node_pages();
+    println!("{:?}", totals);
+    assert!(!totals.is_empty());
+    assert!(numa_maps.iter().any(|m| m.address().is_some()));
+}
+
+#[test]
+fn test_id_maps() {
+    let me = Process::myself().unwrap();
+    let uid_map = me.uid_map().unwrap();
+    let gid_map = me.gid_map().unwrap();
+    println!("{:?} {:?}", uid_map, gid_map);
+
+    let status = me.status().unwrap();
+    // our own IDs must be mapped in our own user namespace
+    assert_eq!(
+        uid_map.to_inside(uid_map.to_outside(status.euid).unwrap()),
+        Some(status.euid)
+    );
+    assert!(gid_map.to_outside(status.egid).is_some());
+
+    if let Some(setgroups) = check_unwrap(&me, me.setgroups()) {
+        println!("{:?}", setgroups);
+    }
+
+    // our map has already been written, so it can't be written again
+    assert!(matches!(me.set_uid_map(&uid_map), Err(ProcError::Other(_))));
+}
+
+#[test]
+fn test_fdinfo() {
+    use std::os::unix::io::AsRawFd;
+
+    let mut file = std::fs::File::open("/proc/self/status").unwrap();
+    let mut buf = [0; 10];
+    file.read_exact(&mut buf).unwrap();
+
+    let me = Process::myself().unwrap();
+    let details = me.fdinfo(file.as_raw_fd()).unwrap();
+    assert_eq!(details.pos, 10);
+    assert!(matches!(details.kind, FDDetailsKind::Generic));
+
+    for fd in me.fd().unwrap() {
+        let fd = fd.unwrap();
+        if let Some(details) = check_unwrap(&me, fd.details(&me)) {
+            println!("{:?} {:?}", fd, details);
+        }
+    }
+}
+
 #[test]
 fn test_network_stuff() {
     let myself = Process::myself().unwrap();
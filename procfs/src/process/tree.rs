@@ -0,0 +1,321 @@
+use super::{all_processes, ProcessSnapshot, SnapshotFields, Stat};
+use crate::ProcResult;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// A process in a [ProcessTree]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ProcessNode {
+    /// The stat info of this process, from the time the tree was built
+    pub stat: Stat,
+    /// The number of open file descriptors, if they could be counted
+    pub fd_count: Option<usize>,
+    /// The command line, if it was collected and could be read
+    ///
+    /// This is empty for kernel threads and zombie processes.
+    pub cmdline: Option<Vec<String>>,
+    /// The PID of the parent of this process, if the parent is part of the tree
+    pub parent: Option<i32>,
+    /// The PIDs of the children of this process, in ascending order
+    pub children: Vec<i32>,
+}
+
+impl ProcessNode {
+    /// The process ID
+    pub fn pid(&self) -> i32 {
+        self.stat.pid
+    }
+
+    /// Returns true if this process is in the foreground process group of its controlling terminal
+    pub fn is_foreground(&self) -> bool {
+        self.stat.tpgid > 0 && self.stat.tpgid == self.stat.pgrp
+    }
+}
+
+/// Resource usage summed over a process and all of its descendants
+///
+/// See [ProcessTree::subtree_totals()]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubtreeTotals {
+    /// The number of processes in the subtree
+    pub processes: usize,
+    /// The number of threads
+    pub threads: u64,
+    /// Resident set size, in pages
+    pub rss: u64,
+    /// Time spent in user mode, in clock ticks
+    pub utime: u64,
+    /// Time spent in kernel mode, in clock ticks
+    pub stime: u64,
+    /// The number of open file descriptors
+    ///
+    /// This only includes the processes whose file descriptors could be counted, see
+    /// [fd_count_unknown](SubtreeTotals::fd_count_unknown).
+    pub fd_count: usize,
+    /// The number of processes whose file descriptors could not be counted (usually because of
+    /// missing permissions)
+    pub fd_count_unknown: usize,
+}
+
+impl SubtreeTotals {
+    /// Total CPU time (user and kernel), in clock ticks
+    pub fn cpu_ticks(&self) -> u64 {
+        self.utime + self.stime
+    }
+
+    /// Resident set size, in bytes
+    pub fn rss_bytes(&self) -> u64 {
+        self.rss * crate::page_size()
+    }
+}
+
+/// The parent/child hierarchy of processes
+///
+/// The tree is built from a point-in-time list of processes, and isn't updated afterwards.
+/// Processes are identified by their PID.
+///
+/// # Example
+///
+/// ```rust
+/// use procfs::process::ProcessTree;
+///
+/// let tree = ProcessTree::new().unwrap();
+/// for root in tree.roots() {
+///     let totals = tree.subtree_totals(root.pid()).unwrap();
+///     println!("{} ({}): {} processes, {} ticks", root.pid(), root.stat.comm, totals.processes, totals.cpu_ticks());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTree {
+    nodes: BTreeMap<i32, ProcessNode>,
+}
+
+impl ProcessTree {
+    /// Builds a tree of all processes in the system
+    ///
+    /// Processes that exit while the tree is built are left out.
+    pub fn new() -> ProcResult<ProcessTree> {
+        let mut snapshots = Vec::new();
+        for snapshot in all_processes()?.snapshots(SnapshotFields::STAT | SnapshotFields::FD_COUNT) {
+            snapshots.push(snapshot?);
+        }
+        Ok(ProcessTree::from_snapshots(snapshots))
+    }
+
+    /// Builds a tree from a list of snapshots
+    ///
+    /// Snapshots without a [stat](ProcessSnapshot::stat) are left out.  If the snapshots include
+    /// [fd_count](ProcessSnapshot::fd_count), it's used for the subtree totals, and if they
+    /// include [cmdline](ProcessSnapshot::cmdline), it's kept in [ProcessNode::cmdline].
+    pub fn from_snapshots<I: IntoIterator<Item = ProcessSnapshot>>(snapshots: I) -> ProcessTree {
+        let mut extras = BTreeMap::new();
+        let stats = snapshots.into_iter().filter_map(|s| {
+            let fd_count = s.fd_count.and_then(|f| f.ok());
+            let cmdline = s.cmdline.and_then(|c| c.ok());
+            extras.insert(s.pid, (fd_count, cmdline));
+            s.stat.and_then(|s| s.ok())
+        });
+        let mut tree = ProcessTree::from_stats(stats);
+        for (pid, (fd_count, cmdline)) in extras {
+            if let Some(node) = tree.nodes.get_mut(&pid) {
+                node.fd_count = fd_count;
+                node.cmdline = cmdline;
+            }
+        }
+        tree
+    }
+
+    /// Builds a tree from a list of [Stat]s
+    pub fn from_stats<I: IntoIterator<Item = Stat>>(stats: I) -> ProcessTree {
+        let mut nodes: BTreeMap<i32, ProcessNode> = stats
+            .into_iter()
+            .map(|stat| {
+                (
+                    stat.pid,
+                    ProcessNode {
+                        stat,
+                        fd_count: None,
+                        cmdline: None,
+                        parent: None,
+                        children: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        let links: Vec<(i32, i32)> = nodes
+            .values()
+            .filter(|n| n.stat.ppid != n.stat.pid && nodes.contains_key(&n.stat.ppid))
+            .map(|n| (n.stat.pid, n.stat.ppid))
+            .collect();
+        // pids are visited in ascending order, so the children end up sorted
+        for (pid, ppid) in links {
+            if let Some(node) = nodes.get_mut(&pid) {
+                node.parent = Some(ppid);
+            }
+            if let Some(parent) = nodes.get_mut(&ppid) {
+                parent.children.push(pid);
+            }
+        }
+
+        ProcessTree { nodes }
+    }
+
+    /// Returns the process with the given PID
+    pub fn get(&self, pid: i32) -> Option<&ProcessNode> {
+        self.nodes.get(&pid)
+    }
+
+    pub fn contains(&self, pid: i32) -> bool {
+        self.nodes.contains_key(&pid)
+    }
+
+    /// Returns the number of processes in the tree
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns an iterator over all processes in the tree, in ascending PID order
+    pub fn iter(&self) -> impl Iterator<Item = &ProcessNode> {
+        self.nodes.values()
+    }
+
+    /// Returns the processes whose parent is not part of the tree
+    ///
+    /// For a tree of all processes, these are `init` (PID 1) and `kthreadd` (PID 2), and the
+    /// processes whose parent exited while the tree was built.
+    pub fn roots(&self) -> impl Iterator<Item = &ProcessNode> {
+        self.nodes.values().filter(|n| n.parent.is_none())
+    }
+
+    /// Returns the processes whose parent process is not part of the tree
+    ///
+    /// Unlike [roots()](ProcessTree::roots), this doesn't include processes without a parent
+    /// (whose parent PID is 0).  Since the kernel reparents orphaned processes, these are usually
+    /// processes whose parent is outside the set of processes that the tree was built from, or
+    /// whose parent exited while the tree was built.
+    pub fn orphans(&self) -> impl Iterator<Item = &ProcessNode> {
+        self.nodes.values().filter(|n| n.parent.is_none() && n.stat.ppid != 0)
+    }
+
+    /// Returns the parent of the given process
+    pub fn parent(&self, pid: i32) -> Option<&ProcessNode> {
+        self.get(pid)?.parent.and_then(|ppid| self.get(ppid))
+    }
+
+    /// Returns the direct children of the given process
+    pub fn children(&self, pid: i32) -> impl Iterator<Item = &ProcessNode> {
+        self.get(pid)
+            .map(|n| n.children.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(move |pid| self.get(*pid))
+    }
+
+    /// Returns the ancestors of the given process, starting with its parent
+    pub fn ancestors(&self, pid: i32) -> Vec<&ProcessNode> {
+        let mut ret = Vec::new();
+        let mut seen = HashSet::new();
+        seen.insert(pid);
+        let mut current = self.parent(pid);
+        while let Some(node) = current {
+            if !seen.insert(node.pid()) {
+                break;
+            }
+            ret.push(node);
+            current = self.parent(node.pid());
+        }
+        ret
+    }
+
+    /// Returns all descendants of the given process (not including the process itself), in
+    /// depth-first order
+    pub fn descendants(&self, pid: i32) -> Vec<&ProcessNode> {
+        let mut ret = Vec::new();
+        let mut seen = HashSet::new();
+        seen.insert(pid);
+        let mut stack: Vec<i32> = self.get(pid).map(|n| n.children.clone()).unwrap_or_default();
+        stack.reverse();
+        while let Some(pid) = stack.pop() {
+            if !seen.insert(pid) {
+                continue;
+            }
+            if let Some(node) = self.get(pid) {
+                ret.push(node);
+                stack.extend(node.children.iter().rev());
+            }
+        }
+        ret
+    }
+
+    /// Returns all processes in the given session
+    pub fn session(&self, session: i32) -> Vec<&ProcessNode> {
+        self.nodes.values().filter(|n| n.stat.session == session).collect()
+    }
+
+    /// Returns all processes in the given process group
+    pub fn process_group(&self, pgrp: i32) -> Vec<&ProcessNode> {
+        self.nodes.values().filter(|n| n.stat.pgrp == pgrp).collect()
+    }
+
+    /// Returns the IDs of all sessions, along with the process groups in each session
+    pub fn sessions(&self) -> BTreeMap<i32, BTreeSet<i32>> {
+        let mut ret: BTreeMap<i32, BTreeSet<i32>> = BTreeMap::new();
+        for node in self.nodes.values() {
+            ret.entry(node.stat.session).or_default().insert(node.stat.pgrp);
+        }
+        ret
+    }
+
+    /// Returns the IDs of all orphaned process groups
+    ///
+    /// A process group is orphaned when no member has a parent in a different process group of
+    /// the same session.  Orphaned process groups don't receive job control signals from their
+    /// terminal, see `credentials(7)`.  Parents that are not part of the tree are treated as
+    /// being outside of the session.
+    pub fn orphaned_process_groups(&self) -> BTreeSet<i32> {
+        let mut groups: BTreeSet<i32> = self.nodes.values().map(|n| n.stat.pgrp).collect();
+        for node in self.nodes.values() {
+            if let Some(parent) = self.parent(node.pid()) {
+                if parent.stat.pgrp != node.stat.pgrp && parent.stat.session == node.stat.session {
+                    groups.remove(&node.stat.pgrp);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Sums the resource usage of the given process and all of its descendants
+    ///
+    /// Returns `None` if the process isn't part of the tree.
+    pub fn subtree_totals(&self, pid: i32) -> Option<SubtreeTotals> {
+        let root = self.get(pid)?;
+        let mut totals = SubtreeTotals::default();
+        for node in std::iter::once(root).chain(self.descendants(pid)) {
+            totals.processes += 1;
+            totals.threads += node.stat.num_threads.max(0) as u64;
+            totals.rss += node.stat.rss;
+            totals.utime += node.stat.utime;
+            totals.stime += node.stat.stime;
+            match node.fd_count {
+                Some(count) => totals.fd_count += count,
+                None => totals.fd_count_unknown += 1,
+            }
+        }
+        Some(totals)
+    }
+}
+
+impl<'a> IntoIterator for &'a ProcessTree {
+    type IntoIter = std::collections::btree_map::Values<'a, i32, ProcessNode>;
+    type Item = &'a ProcessNode;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.values()
+    }
+}
@@ -0,0 +1,232 @@
+use super::{all_processes, Io, Process, ProcessIdentity, Stat, Status};
+use crate::{ProcError, ProcResult};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// The cumulative CPU, I/O and scheduling counters of a process or thread
+///
+/// See [UsageSample]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UsageEntry {
+    /// The PID of the process (or the TID of the thread) and its start time
+    pub identity: ProcessIdentity,
+    /// The command name
+    pub comm: String,
+    /// Time spent in user mode, in clock ticks
+    pub utime: u64,
+    /// Time spent in kernel mode, in clock ticks
+    pub stime: u64,
+    /// Bytes read from storage (see [Io::read_bytes])
+    ///
+    /// This is `None` if the caller isn't allowed to read the I/O counters.
+    pub read_bytes: Option<u64>,
+    /// Bytes written to storage (see [Io::write_bytes])
+    ///
+    /// This is `None` if the caller isn't allowed to read the I/O counters.
+    pub write_bytes: Option<u64>,
+    /// Number of voluntary context switches
+    pub voluntary_ctxt_switches: Option<u64>,
+    /// Number of involuntary context switches
+    pub nonvoluntary_ctxt_switches: Option<u64>,
+}
+
+impl UsageEntry {
+    fn new(stat: Stat, io: ProcResult<Io>, status: ProcResult<Status>) -> ProcResult<UsageEntry> {
+        let io = match io {
+            Ok(io) => Some(io),
+            Err(ProcError::PermissionDenied(_)) | Err(ProcError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        let status = match status {
+            Ok(status) => Some(status),
+            Err(ProcError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(UsageEntry {
+            identity: stat.identity(),
+            utime: stat.utime,
+            stime: stat.stime,
+            comm: stat.comm,
+            read_bytes: io.as_ref().map(|io| io.read_bytes),
+            write_bytes: io.as_ref().map(|io| io.write_bytes),
+            voluntary_ctxt_switches: status.as_ref().and_then(|s| s.voluntary_ctxt_switches),
+            nonvoluntary_ctxt_switches: status.as_ref().and_then(|s| s.nonvoluntary_ctxt_switches),
+        })
+    }
+}
+
+/// The rates of a process or thread between two [UsageSample]s
+///
+/// See [UsageSample::rates_since()]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UsageRates {
+    /// The PID of the process (or the TID of the thread) and its start time
+    pub identity: ProcessIdentity,
+    /// The command name
+    pub comm: String,
+    /// True if the process wasn't present in the previous sample
+    ///
+    /// In this case, the rates are computed from all activity since the process started.
+    pub is_new: bool,
+    /// Percentage of one CPU spent in user and kernel mode
+    ///
+    /// This can be more than 100 for multi-threaded processes.
+    pub cpu_percent: f64,
+    /// Percentage of one CPU spent in user mode
+    pub user_percent: f64,
+    /// Percentage of one CPU spent in kernel mode
+    pub system_percent: f64,
+    /// Bytes read from storage per second
+    pub read_bytes_per_sec: Option<f64>,
+    /// Bytes written to storage per second
+    pub write_bytes_per_sec: Option<f64>,
+    /// Voluntary context switches per second
+    pub voluntary_ctxt_switches_per_sec: Option<f64>,
+    /// Involuntary context switches per second
+    pub nonvoluntary_ctxt_switches_per_sec: Option<f64>,
+}
+
+/// The cumulative counters of a set of processes (or threads) at one point in time
+///
+/// Two samples can be compared with [UsageSample::rates_since()] to get `top`-like CPU usage,
+/// I/O throughput and context switch rates.  Since PIDs can be reused, entries are only
+/// compared when both their PID and their start time match.
+///
+/// # Example
+///
+/// ```rust
+/// use procfs::process::UsageSample;
+///
+/// let before = UsageSample::processes().unwrap();
+/// std::thread::sleep(std::time::Duration::from_millis(100));
+/// let after = UsageSample::processes().unwrap();
+/// for rates in after.rates_since(&before) {
+///     if rates.cpu_percent > 0.0 {
+///         println!("{} {}: {:.1}%", rates.identity.pid, rates.comm, rates.cpu_percent);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UsageSample {
+    /// When this sample was taken
+    ///
+    /// This is the time just before the first entry was read.
+    pub taken: Instant,
+    /// The entries in this sample, keyed by PID (or TID)
+    pub entries: BTreeMap<i32, UsageEntry>,
+}
+
+impl UsageSample {
+    /// Takes a sample of all processes in the system
+    ///
+    /// Processes that exit while the sample is taken are left out.
+    pub fn processes() -> ProcResult<UsageSample> {
+        // taken before the walk, since walking all of /proc can take a while on a busy system
+        let taken = Instant::now();
+        let mut entries = BTreeMap::new();
+        for process in all_processes()? {
+            let entry = process.and_then(|p| UsageEntry::new(p.stat()?, p.io(), p.status()));
+            match entry {
+                Ok(entry) => {
+                    entries.insert(entry.identity.pid, entry);
+                }
+                Err(ProcError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(UsageSample { taken, entries })
+    }
+
+    /// Takes a sample of all threads of the given process
+    ///
+    /// Threads that exit while the sample is taken are left out.
+    pub fn tasks(process: &Process) -> ProcResult<UsageSample> {
+        let taken = Instant::now();
+        let mut entries = BTreeMap::new();
+        for task in process.tasks()? {
+            let entry = task.and_then(|t| UsageEntry::new(t.stat()?, t.io(), t.status()));
+            match entry {
+                Ok(entry) => {
+                    entries.insert(entry.identity.pid, entry);
+                }
+                Err(ProcError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(UsageSample { taken, entries })
+    }
+
+    /// Computes the rates of every entry in this sample since a previous sample
+    ///
+    /// Entries that are new in this sample (including PIDs that were reused by a new process)
+    /// are compared against zero, so their rates include all activity since they started.  The
+    /// result is sorted by PID.
+    pub fn rates_since(&self, previous: &UsageSample) -> Vec<UsageRates> {
+        let elapsed = self.taken.saturating_duration_since(previous.taken).as_secs_f64();
+        let ticks_per_second = crate::ticks_per_second() as f64;
+
+        let per_sec = |delta: u64| if elapsed > 0.0 { delta as f64 / elapsed } else { 0.0 };
+        let percent = |ticks: u64| per_sec(ticks) / ticks_per_second * 100.0;
+        let delta = |now: Option<u64>, prev: Option<u64>, is_new: bool| match (now, prev, is_new) {
+            (Some(now), _, true) => Some(now),
+            (Some(now), Some(prev), false) => Some(now.saturating_sub(prev)),
+            _ => None,
+        };
+
+        self.entries
+            .values()
+            .map(|entry| {
+                let prev = previous
+                    .entries
+                    .get(&entry.identity.pid)
+                    .filter(|p| p.identity == entry.identity);
+                let is_new = prev.is_none();
+                let prev_utime = prev.map_or(0, |p| p.utime);
+                let prev_stime = prev.map_or(0, |p| p.stime);
+                let utime = entry.utime.saturating_sub(prev_utime);
+                let stime = entry.stime.saturating_sub(prev_stime);
+
+                UsageRates {
+                    identity: entry.identity,
+                    comm: entry.comm.clone(),
+                    is_new,
+                    cpu_percent: percent(utime + stime),
+                    user_percent: percent(utime),
+                    system_percent: percent(stime),
+                    read_bytes_per_sec: delta(entry.read_bytes, prev.and_then(|p| p.read_bytes), is_new).map(per_sec),
+                    write_bytes_per_sec: delta(entry.write_bytes, prev.and_then(|p| p.write_bytes), is_new)
+                        .map(per_sec),
+                    voluntary_ctxt_switches_per_sec: delta(
+                        entry.voluntary_ctxt_switches,
+                        prev.and_then(|p| p.voluntary_ctxt_switches),
+                        is_new,
+                    )
+                    .map(per_sec),
+                    nonvoluntary_ctxt_switches_per_sec: delta(
+                        entry.nonvoluntary_ctxt_switches,
+                        prev.and_then(|p| p.nonvoluntary_ctxt_switches),
+                        is_new,
+                    )
+                    .map(per_sec),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the entries of a previous sample that are no longer present in this sample
+    ///
+    /// This includes processes whose PID has since been reused by a new process.
+    pub fn exited_since<'a>(&self, previous: &'a UsageSample) -> Vec<&'a UsageEntry> {
+        previous
+            .entries
+            .values()
+            .filter(|p| {
+                self.entries
+                    .get(&p.identity.pid)
+                    .map_or(true, |e| e.identity != p.identity)
+            })
+            .collect()
+    }
+}
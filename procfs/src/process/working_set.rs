@@ -0,0 +1,186 @@
+use super::{ClearRefs, MemoryMap, MemoryPageFlags, PageInfo, Process, SwapPageFlags};
+use crate::ProcResult;
+use std::time::{Duration, Instant};
+
+/// What a [WorkingSetTracker] measures
+///
+/// New variants to this enum may be added at any time (even without a major or minor semver bump).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WorkingSetMode {
+    /// Pages that were written to, using the soft-dirty bits in `/proc/<pid>/pagemap`
+    ///
+    /// This requires a kernel built with `CONFIG_MEM_SOFT_DIRTY`.  Without it, no pages are
+    /// ever reported as written.
+    SoftDirty,
+    /// Pages that were read or written, using the `Referenced` fields in `/proc/<pid>/smaps`
+    Referenced,
+}
+
+/// The pages of one mapping that were used while a [WorkingSetTracker] was running
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct WorkingSetMapping {
+    /// The mapping
+    pub map: MemoryMap,
+    /// The number of pages that were written (or touched)
+    pub pages: u64,
+    /// The size of those pages, in bytes
+    pub bytes: u64,
+}
+
+/// The pages that a process used while a [WorkingSetTracker] was running
+///
+/// Created by [WorkingSetTracker::measure()].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct WorkingSetReport {
+    /// How the pages were measured
+    pub mode: WorkingSetMode,
+    /// The time since the bits were cleared
+    pub elapsed: Duration,
+    /// Every mapping of the process, including those that weren't used
+    pub mappings: Vec<WorkingSetMapping>,
+    /// The total number of pages that were written (or touched)
+    pub pages: u64,
+    /// The total size of those pages, in bytes
+    pub bytes: u64,
+}
+
+/// Measures which pages of a process are written to (or touched) over a period of time
+///
+/// Creating a tracker clears the soft-dirty (or referenced) bits of all pages of the process.
+/// After some time, [measure()](WorkingSetTracker::measure) reports the pages whose bits were
+/// set again, per mapping and in total.
+///
+/// Clearing the bits requires write access to `/proc/<pid>/clear_refs` (so the caller must own the
+/// process), and both modes need a kernel built with `CONFIG_PROC_PAGE_MONITOR`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use procfs::process::{Process, WorkingSetMode};
+///
+/// let process = Process::myself().unwrap();
+/// let tracker = process.working_set_tracker(WorkingSetMode::SoftDirty).unwrap();
+/// std::thread::sleep(std::time::Duration::from_secs(10));
+/// let report = tracker.measure().unwrap();
+/// println!("{} bytes written in {:?}", report.bytes, report.elapsed);
+/// ```
+#[derive(Debug)]
+pub struct WorkingSetTracker<'a> {
+    process: &'a Process,
+    mode: WorkingSetMode,
+    started: Instant,
+}
+
+impl Process {
+    /// Starts measuring which pages of this process are written to (or touched)
+    ///
+    /// See [WorkingSetTracker].
+    pub fn working_set_tracker(&self, mode: WorkingSetMode) -> ProcResult<WorkingSetTracker<'_>> {
+        let mut tracker = WorkingSetTracker {
+            process: self,
+            mode,
+            started: Instant::now(),
+        };
+        tracker.reset()?;
+        Ok(tracker)
+    }
+}
+
+impl WorkingSetTracker<'_> {
+    /// How this tracker measures the pages
+    pub fn mode(&self) -> WorkingSetMode {
+        self.mode
+    }
+
+    /// The time since the bits were cleared
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Clears the bits again, starting a new measurement
+    pub fn reset(&mut self) -> ProcResult<()> {
+        let clear = match self.mode {
+            WorkingSetMode::SoftDirty => ClearRefs::SoftDirty,
+            WorkingSetMode::Referenced => ClearRefs::PGReferencedAll,
+        };
+        self.process.clear_refs(clear)?;
+        self.started = Instant::now();
+        Ok(())
+    }
+
+    /// Reports the pages that were written to (or touched) since the bits were cleared
+    ///
+    /// This doesn't clear the bits, so calling it repeatedly reports the usage over a growing
+    /// period of time.
+    pub fn measure(&self) -> ProcResult<WorkingSetReport> {
+        let elapsed = self.elapsed();
+        let mappings = match self.mode {
+            WorkingSetMode::SoftDirty => self.soft_dirty_mappings()?,
+            WorkingSetMode::Referenced => self.referenced_mappings()?,
+        };
+
+        Ok(WorkingSetReport {
+            mode: self.mode,
+            elapsed,
+            pages: mappings.iter().map(|m| m.pages).sum(),
+            bytes: mappings.iter().map(|m| m.bytes).sum(),
+            mappings,
+        })
+    }
+
+    fn soft_dirty_mappings(&self) -> ProcResult<Vec<WorkingSetMapping>> {
+        let page_size = crate::page_size();
+        let mut pagemap = self.process.pagemap()?;
+        let mut ret = Vec::new();
+
+        for map in self.process.maps()? {
+            let mut pages = 0;
+            pagemap.for_each_in_map(&map, |_, batch| {
+                for info in batch {
+                    // pages that were never populated are also reported as soft-dirty in new mappings,
+                    // so only count pages that are present or swapped
+                    let written = match info {
+                        PageInfo::MemoryPage(flags) => {
+                            flags.contains(MemoryPageFlags::PRESENT | MemoryPageFlags::SOFT_DIRTY)
+                        }
+                        PageInfo::SwapPage(flags) => flags.contains(SwapPageFlags::SOFT_DIRTY),
+                    };
+                    if written {
+                        pages += 1;
+                    }
+                }
+            })?;
+            ret.push(WorkingSetMapping {
+                map,
+                pages,
+                bytes: pages * page_size,
+            });
+        }
+        Ok(ret)
+    }
+
+    fn referenced_mappings(&self) -> ProcResult<Vec<WorkingSetMapping>> {
+        let page_size = crate::page_size();
+        Ok(self
+            .process
+            .smaps()?
+            .into_iter()
+            .map(|map| {
+                let entry = map.extension.smaps_entry();
+                let page_size = if entry.kernel_page_size > 0 {
+                    entry.kernel_page_size
+                } else {
+                    page_size
+                };
+                WorkingSetMapping {
+                    pages: entry.referenced / page_size,
+                    bytes: entry.referenced,
+                    map,
+                }
+            })
+            .collect())
+    }
+}
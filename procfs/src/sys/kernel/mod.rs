@@ -310,6 +310,41 @@ pub fn pid_max() -> ProcResult<i32> {
     read_value("/proc/sys/kernel/pid_max")
 }
 
+/// Returns the number of the highest capability supported by the running kernel
+///
+/// This can be used with [Capabilities::supported()](crate::process::Capabilities::supported) to
+/// find all capabilities known to the kernel, including any that don't have a name in this crate.
+///
+/// This is taken from `/proc/sys/kernel/cap_last_cap`
+///
+/// (since Linux 3.2)
+pub fn cap_last_cap() -> ProcResult<u32> {
+    read_value("/proc/sys/kernel/cap_last_cap")
+}
+
+/// Returns true if the kernel automatically creates a new autogroup for each session
+///
+/// See `sched(7)` for details about autogroups, and [Process::set_autogroup_nice()](crate::process::Process::set_autogroup_nice)
+/// to adjust the nice value of an autogroup.
+///
+/// This is taken from `/proc/sys/kernel/sched_autogroup_enabled`
+///
+/// (since Linux 2.6.38 and requires CONFIG_SCHED_AUTOGROUP)
+pub fn sched_autogroup_enabled() -> ProcResult<bool> {
+    let value: u8 = read_value("/proc/sys/kernel/sched_autogroup_enabled")?;
+    Ok(value != 0)
+}
+
+/// Enables or disables the automatic creation of autogroups for new sessions
+///
+/// Existing autogroups are not affected.  This requires the `CAP_SYS_ADMIN` capability, and
+/// will return `ProcError::PermissionDenied` otherwise.
+///
+/// (since Linux 2.6.38 and requires CONFIG_SCHED_AUTOGROUP)
+pub fn set_sched_autogroup_enabled(enabled: bool) -> ProcResult<()> {
+    write_value("/proc/sys/kernel/sched_autogroup_enabled", enabled as u8)
+}
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 /// Represents the data from `/proc/sys/kernel/sem`
 pub struct SemaphoreLimits {
@@ -575,6 +610,24 @@ mod tests {
         assert!(pid_max().is_ok());
     }
 
+    #[test]
+    fn test_cap_last_cap() {
+        let last_cap = cap_last_cap().unwrap();
+        let supported = crate::process::Capabilities::supported(last_cap);
+        println!("{} {:?} unknown: {:x}", last_cap, supported, supported.unknown());
+        assert!(supported.contains(crate::process::Capabilities::CAP_SYSLOG));
+    }
+
+    #[test]
+    fn test_sched_autogroup_enabled() {
+        match sched_autogroup_enabled() {
+            Ok(enabled) => println!("sched_autogroup_enabled: {}", enabled),
+            // requires CONFIG_SCHED_AUTOGROUP
+            Err(ProcError::NotFound(_)) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
     #[test]
     fn test_semaphore_limits() {
         // Note that the below string has tab characters in it. Make sure to not remove them.